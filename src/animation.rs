@@ -0,0 +1,130 @@
+use crate::sprites::Sprite;
+
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Playback {
+    Loop,
+    /// stays on the last frame once done
+    OneShot,
+}
+
+/// Sprites shown one after another, each for its own number of game frames.
+#[derive(Debug)]
+pub struct Animation {
+    frames: &'static [(Sprite, u8)],
+    playback: Playback,
+}
+
+impl Animation {
+    pub const fn new(frames: &'static [(Sprite, u8)], playback: Playback) -> Self {
+        Self { frames, playback }
+    }
+
+    /// How many game frames one play through takes.
+    pub fn duration(&self) -> u32 {
+        self.frames.iter().map(|&(_, ticks)| ticks as u32).sum()
+    }
+}
+
+/// Plays an animation on an object. Without one the object is drawn with its still sprite.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Animator {
+    animation: Option<&'static Animation>,
+    frame: usize,
+    ticks: u8,
+}
+
+impl Animator {
+    pub const fn new() -> Self {
+        Self { animation: None, frame: 0, ticks: 0 }
+    }
+
+    /// Starts `animation` from the beginning, unless it's already playing.
+    pub fn play(&mut self, animation: &'static Animation) {
+        if !self.animation.is_some_and(|playing| std::ptr::eq(playing, animation)) {
+            *self = Self { animation: Some(animation), frame: 0, ticks: 0 };
+        }
+    }
+
+    pub fn stop(&mut self) {
+        *self = Self::new();
+    }
+
+    pub fn step(&mut self) {
+        let animation = match self.animation {
+            Some(animation) => animation,
+            None => return,
+        };
+        self.ticks += 1;
+        if self.ticks < animation.frames[self.frame].1 {
+            return;
+        }
+        self.ticks = 0;
+        self.frame += 1;
+        if self.frame == animation.frames.len() {
+            self.frame = match animation.playback {
+                Playback::Loop => 0,
+                Playback::OneShot => {
+                    self.ticks = animation.frames[self.frame - 1].1;
+                    self.frame - 1
+                },
+            };
+        }
+    }
+
+    /// Whether a one-shot animation has played through.
+    pub fn is_finished(&self) -> bool {
+        self.animation.is_some_and(|animation| {
+            animation.playback == Playback::OneShot
+                && self.frame + 1 == animation.frames.len()
+                && self.ticks >= animation.frames[self.frame].1
+        })
+    }
+
+    pub fn current_sprite(&self) -> Option<Sprite> {
+        self.animation.map(|animation| animation.frames[self.frame].0)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sprites;
+
+    static BLINK: Animation = Animation::new(&[(sprites::BOX, 2), (sprites::NORMAL, 1)], Playback::Loop);
+    static BREAK: Animation = Animation::new(&[(sprites::BOX, 1), (sprites::NORMAL, 2)], Playback::OneShot);
+
+    #[test]
+    fn test_looping_animation() {
+        let mut animator = Animator::default();
+        assert!(animator.current_sprite().is_none());
+
+        animator.play(&BLINK);
+        let mut shown = Vec::new();
+        for _ in 0..6 {
+            shown.push(animator.current_sprite().unwrap());
+            animator.step();
+        }
+        let (a, b) = (sprites::BOX, sprites::NORMAL);
+        assert_eq!(shown, [a, a, b, a, a, b]);
+        assert!(!animator.is_finished());
+    }
+
+    #[test]
+    fn test_one_shot_animation() {
+        let mut animator = Animator::default();
+        animator.play(&BREAK);
+        for _ in 0..BREAK.duration() {
+            assert!(!animator.is_finished());
+            animator.step();
+        }
+        assert!(animator.is_finished());
+
+        // holds the last frame, and playing it again doesn't restart it
+        animator.step();
+        animator.play(&BREAK);
+        assert!(animator.is_finished());
+        assert_eq!(animator.current_sprite(), Some(sprites::NORMAL));
+    }
+}
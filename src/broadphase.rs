@@ -0,0 +1,140 @@
+use crate::level_object::LevelObject;
+
+
+/// Sweep-and-prune over the x axis. The sort order is kept between frames so
+/// the insertion sort only has to fix up the few objects that moved past each other.
+#[derive(Clone, Debug, Default)]
+pub struct SweepAndPrune {
+    order: Vec<usize>,
+    pairs: Vec<(usize, usize)>,
+}
+
+impl SweepAndPrune {
+    pub const fn new() -> Self {
+        Self {
+            order: Vec::new(),
+            pairs: Vec::new(),
+        }
+    }
+
+    /// Pairs `(i, j)` with `i < j` whose bounding boxes may overlap, in index order.
+    pub fn update(&mut self, objects: &[LevelObject]) -> &[(usize, usize)] {
+        // objects were added or removed, start over
+        if self.order.len() != objects.len() {
+            self.order.clear();
+            self.order.extend(0..objects.len());
+        }
+
+        let min_x = |i: usize| objects[i].bounds().0.x;
+
+        for k in 1..self.order.len() {
+            let mut l = k;
+            while l > 0 && min_x(self.order[l - 1]) > min_x(self.order[l]) {
+                self.order.swap(l - 1, l);
+                l -= 1;
+            }
+        }
+
+        self.pairs.clear();
+        for (k, &i) in self.order.iter().enumerate() {
+            let (pos_i, box_i) = objects[i].bounds();
+            let max_x = pos_i.x + box_i.width;
+
+            for &j in self.order[k + 1..].iter() {
+                let (pos_j, box_j) = objects[j].bounds();
+                if pos_j.x > max_x {
+                    break;
+                }
+                if pos_i.y <= pos_j.y + box_j.height && pos_j.y <= pos_i.y + box_i.height {
+                    self.pairs.push((i.min(j), i.max(j)));
+                }
+            }
+        }
+        self.pairs.sort_unstable();
+
+        &self.pairs
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level_object::LevelObjectKind;
+    use crate::position::Position;
+    use crate::vector::Vector;
+    use std::time::Instant;
+
+    fn scattered_boxes(n: usize) -> Vec<LevelObject> {
+        // small lcg so runs are repeatable
+        let mut seed: u32 = 12345;
+        let mut next = || {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            (seed >> 16) as f32 / 65536.0
+        };
+        (0..n).map(|_| LevelObject::new(
+            LevelObjectKind::Box,
+            Position::new(next() * 400.0 - 200.0, next() * 60.0),
+            Vector::new(0.0, 0.0),
+        )).collect()
+    }
+
+    fn all_pairs(objects: &[LevelObject]) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        for i in 0..objects.len() {
+            for j in (i + 1)..objects.len() {
+                if objects[i].collides_with(&objects[j]) {
+                    pairs.push((i, j));
+                }
+            }
+        }
+        pairs
+    }
+
+    #[test]
+    fn test_finds_every_colliding_pair() {
+        let objects = scattered_boxes(150);
+        let mut broadphase = SweepAndPrune::new();
+        let candidates = broadphase.update(&objects).to_vec();
+
+        for pair in all_pairs(&objects) {
+            assert!(candidates.contains(&pair));
+        }
+    }
+
+    #[test]
+    fn test_handles_objects_being_removed() {
+        let mut objects = scattered_boxes(20);
+        let mut broadphase = SweepAndPrune::new();
+        broadphase.update(&objects);
+
+        objects.truncate(10);
+        assert!(broadphase.update(&objects).iter().all(|&(i, j)| i < j && j < 10));
+    }
+
+    /// Native benchmark, run with
+    /// `cargo test --release --target <host> --no-default-features -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_sweep_and_prune_vs_all_pairs() {
+        const FRAMES: u32 = 600;
+        for n in [50, 100, 150, 200] {
+            let objects = scattered_boxes(n);
+
+            let start = Instant::now();
+            for _ in 0..FRAMES {
+                std::hint::black_box(all_pairs(std::hint::black_box(&objects)));
+            }
+            let brute = start.elapsed() / FRAMES;
+
+            let mut broadphase = SweepAndPrune::new();
+            let start = Instant::now();
+            for _ in 0..FRAMES {
+                std::hint::black_box(broadphase.update(std::hint::black_box(&objects)));
+            }
+            let swept = start.elapsed() / FRAMES;
+
+            println!("{:>3} objects: all pairs {:>9.2?}/frame, sweep and prune {:>9.2?}/frame", n, brute, swept);
+        }
+    }
+}
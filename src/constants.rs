@@ -2,9 +2,59 @@ use crate::{vector::Vector, position::Position};
 
 pub const TIME_STEP: f32 = 1.0 / 60.0;
 pub const BOUNCE_DAMPING_FACTOR: f32 = 0.35;
-pub const FRICTION_DAMPING_FACTOR: f32 = 0.05;
+pub const STATIC_FRICTION: f32 = 0.6;
+pub const KINETIC_FRICTION: f32 = 0.4;
 pub const COLLISION_DAMPING_FACTOR: f32 = 0.05;
 pub const GRAVITY: Vector = Vector::new(0., -50.0);
 pub const ORIGIN: Position = Position::new(0.0, 0.0);
 pub const RADIUS: f32 = 15.0;
 pub const THRESHOLD_VELOCITY: f32 = 0.001;
+pub const CONTACT_SLOP: f32 = 0.1;
+/// share of its spin a body resting flat on the ground loses each step
+pub const GROUND_ANGULAR_DAMPING: f32 = 0.1;
+pub const SLEEP_VELOCITY: f32 = 1.5;
+pub const SLEEP_ANGULAR_VELOCITY: f32 = 0.2;
+pub const SLEEP_FRAMES: u16 = 30;
+/// frames after a launch before the next cow is loaded even if the level is still moving
+pub const MAX_SETTLE_FRAMES: u16 = 600;
+pub const HIT_POINTS: f32 = 10.0;
+pub const IMPACT_DAMAGE_THRESHOLD: f32 = 5.0;
+pub const TRAJECTORY_STEPS: usize = 180;
+pub const TRAJECTORY_DOT_SPACING: usize = 4;
+pub const LONGHORN_DASH_SPEED: f32 = 60.0;
+pub const DAIRY_SPLIT_ANGLE: f32 = 0.3;
+pub const CHOCOLATE_SLAM_SPEED: f32 = 120.0;
+pub const CHOCOLATE_SLAM_MASS: f32 = 16.0;
+pub const AMMO_SPACING: f32 = 2.0;
+pub const ALERT_IMPULSE: f32 = 50.0;
+/// contacts with a patrolling enemy softer than this leave sleeping objects asleep
+pub const PATROL_WAKE_IMPULSE: f32 = 20.0;
+pub const FLEE_FRAMES: u16 = 90;
+pub const CAMERA_LERP: f32 = 0.1;
+/// where the followed cow can move without scrolling, in pixels from the bottom-left of the screen
+pub const CAMERA_DEAD_ZONE_MIN: Vector = Vector::new(16.0, 24.0);
+pub const CAMERA_DEAD_ZONE_MAX: Vector = Vector::new(96.0, 136.0);
+/// from the slingshot to the anchor point of the frame a level starts with
+pub const CAMERA_HOME_OFFSET: Vector = Vector::new(-20.0, -92.0);
+pub const CAMERA_MARGIN: f32 = 40.0;
+pub const CAMERA_FIT_PADDING: f32 = 12.0;
+pub const MIN_ZOOM: f32 = 0.25;
+/// pixels per frame when scrolling with the arrows
+pub const PAN_SPEED: f32 = 2.0;
+pub const MAX_PARTICLES: usize = 64;
+/// hits spawn one particle per this much impulse, softer ones none at all
+pub const PARTICLE_IMPULSE: f32 = 20.0;
+pub const MAX_BURST: usize = 8;
+pub const SPLINTERS: usize = 12;
+/// hits softer than this don't shake the screen
+pub const SHAKE_IMPULSE: f32 = 150.0;
+/// pixels of shake per unit of impulse
+pub const SHAKE_PER_IMPULSE: f32 = 0.006;
+pub const MAX_SHAKE: f32 = 5.0;
+pub const SHAKE_DECAY: f32 = 0.8;
+/// a hit-stop lasts a frame for every this much impulse
+pub const HIT_STOP_IMPULSE: f32 = 400.0;
+pub const MAX_HIT_STOP: u8 = 4;
+pub const PALETTE_FADE_FRAMES: u8 = 30;
+/// how far inside the edge of the screen off-screen indicators are drawn, in pixels
+pub const INDICATOR_INSET: i32 = 2;
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+use crate::constants::{TIME_STEP, ALERT_IMPULSE, FLEE_FRAMES};
+use crate::level_object::Hit;
+use crate::position::Position;
+use crate::vector::Vector;
+
+
+/// What an enemy does on its own, set per enemy in the level file.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub enum Behaviour {
+    /// stays where it was put
+    #[default]
+    Fixed,
+    Patrol(Patrol),
+}
+
+impl Behaviour {
+    pub const fn is_patrol(&self) -> bool {
+        matches!(self, Self::Patrol(_))
+    }
+
+    /// Horizontal speed the enemy centered at `center` wants to move at, if it moves at all.
+    pub fn steer(&mut self, center: Position, hits: &[Hit]) -> Option<f32> {
+        match self {
+            Self::Fixed => None,
+            Self::Patrol(patrol) => Some(patrol.walking_speed(center, hits)),
+        }
+    }
+}
+
+/// Walks back and forth between two x positions (e.g. the ends of a platform)
+/// and runs off for a while when something lands nearby.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct Patrol {
+    waypoints: [f32; 2],
+    speed: f32,
+    /// hits closer than this send the enemy running
+    alert_radius: f32,
+    flee_speed: f32,
+    /// index of the waypoint being walked to
+    #[serde(skip)]
+    target: usize,
+    #[serde(skip)]
+    flee_frames: u16,
+    #[serde(skip)]
+    flee_direction: f32,
+}
+
+impl Patrol {
+    pub const fn new(waypoints: [f32; 2], speed: f32, alert_radius: f32, flee_speed: f32) -> Self {
+        Self { waypoints, speed, alert_radius, flee_speed, target: 0, flee_frames: 0, flee_direction: 0.0 }
+    }
+
+    pub const fn is_fleeing(&self) -> bool {
+        self.flee_frames > 0
+    }
+
+    pub fn walking_speed(&mut self, center: Position, hits: &[Hit]) -> f32 {
+        // run away from the latest hard hit in range
+        for hit in hits.iter().filter(|hit| hit.impulse >= ALERT_IMPULSE) {
+            if Vector::between(hit.point, center).magnitude() <= self.alert_radius {
+                self.flee_frames = FLEE_FRAMES;
+                self.flee_direction = if center.x < hit.point.x { -1.0 } else { 1.0 };
+            }
+        }
+        if self.flee_frames > 0 {
+            self.flee_frames -= 1;
+            return self.flee_direction * self.flee_speed;
+        }
+
+        if (self.waypoints[self.target] - center.x).abs() <= self.speed * TIME_STEP {
+            self.target = 1 - self.target;
+        }
+        (self.waypoints[self.target] - center.x).signum() * self.speed
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level_object::LevelObjectKind;
+
+    fn landing(point: Position, impulse: f32) -> Hit {
+        Hit { point, impulse, kind: LevelObjectKind::Box, other: None }
+    }
+
+    fn patrol() -> Patrol {
+        Patrol::new([0.0, 20.0], 10.0, 30.0, 25.0)
+    }
+
+    #[test]
+    fn test_patrol_turns_at_waypoints() {
+        let mut patrol = patrol();
+        assert_eq!(patrol.walking_speed(Position::new(10.0, 4.0), &[]), -10.0);
+        assert_eq!(patrol.walking_speed(Position::new(0.1, 4.0), &[]), 10.0);
+        assert_eq!(patrol.walking_speed(Position::new(10.0, 4.0), &[]), 10.0);
+        assert_eq!(patrol.walking_speed(Position::new(19.9, 4.0), &[]), -10.0);
+    }
+
+    #[test]
+    fn test_patrol_flees_nearby_hits() {
+        let mut patrol = patrol();
+        let center = Position::new(10.0, 4.0);
+
+        // soft touches and faraway crashes are ignored
+        let ignored = [
+            landing(Position::new(12.0, 4.0), ALERT_IMPULSE / 2.0),
+            landing(Position::new(80.0, 4.0), ALERT_IMPULSE * 10.0),
+        ];
+        assert_eq!(patrol.walking_speed(center, &ignored), -10.0);
+        assert!(!patrol.is_fleeing());
+
+        let crash = [landing(Position::new(15.0, 4.0), ALERT_IMPULSE)];
+        assert_eq!(patrol.walking_speed(center, &crash), -25.0);
+        for _ in 1..FLEE_FRAMES {
+            assert_eq!(patrol.walking_speed(center, &[]), -25.0);
+        }
+        assert!(!patrol.is_fleeing());
+        assert_eq!(patrol.walking_speed(center, &[]), -10.0);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::level_object::Rect;
+use crate::constants::{CAMERA_LERP, CAMERA_DEAD_ZONE_MIN, CAMERA_DEAD_ZONE_MAX, CAMERA_FIT_PADDING, MIN_ZOOM, SHAKE_IMPULSE, SHAKE_PER_IMPULSE, MAX_SHAKE, SHAKE_DECAY, HIT_STOP_IMPULSE, MAX_HIT_STOP};
 use crate::wasm4::*;
 use crate::position::Position;
 use crate::vector::Vector;
@@ -7,7 +8,21 @@ use crate::vector::Vector;
 #[derive(Copy, Clone, Debug)]
 pub struct Frame {
     anchor_point: Position,
+    /// area on screen, in units
     bbox: Rect,
+    /// pixels per unit
+    zoom: f32,
+    /// a locked frame stays put instead of following the player
+    locked: bool,
+    /// where free-look panning started, the frame doesn't move on its own until it ends
+    pan_origin: Option<Position>,
+    /// how far the screen shakes, in pixels, fades every frame
+    shake: f32,
+    /// added to the anchor point when drawing, in units
+    shake_offset: Vector,
+    seed: u32,
+    /// frames left with the simulation paused
+    hit_stop: u8,
 }
 
 
@@ -16,6 +31,13 @@ impl Frame {
         Self {
             anchor_point,
             bbox: Rect::new(160.0, 160.0),
+            zoom: 1.0,
+            locked: false,
+            pan_origin: None,
+            shake: 0.0,
+            shake_offset: Vector::new(0.0, 0.0),
+            seed: 1,
+            hit_stop: 0,
         }
     }
 
@@ -28,26 +50,149 @@ impl Frame {
         self.anchor_point.y += offset.y;
     }
 
-    pub fn from_px_to_units(&self, x: u32, y: u32) -> Position {
+    pub const fn get_zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    pub fn set_zoom(&mut self, zoom: f32) {
+        self.zoom = zoom;
+        self.bbox = Rect::new(SCREEN_SIZE as f32 / zoom, SCREEN_SIZE as f32 / zoom);
+    }
+
+    pub fn zoom_to(&mut self, zoom: f32) {
+        if !self.locked && !self.is_panning() {
+            self.set_zoom(self.zoom + (zoom - self.zoom) * CAMERA_LERP);
+        }
+    }
+
+    /// Closest anchor point to `anchor_point` that keeps the frame inside `bounds`.
+    fn clamp_anchor(&self, anchor_point: Position, bounds: (Position, Rect)) -> Position {
+        // bounds smaller than the frame end up centered
+        let clamp = |anchor: f32, min: f32, size: f32, shown: f32| if size < shown {
+            min + (size - shown) / 2.0
+        } else {
+            anchor.clamp(min, min + size - shown)
+        };
+        let (min, Rect { width, height }) = bounds;
+        Position::new(
+            clamp(anchor_point.x, min.x, width, self.bbox.width),
+            clamp(anchor_point.y, min.y, height, self.bbox.height),
+        )
+    }
+
+    /// Zoom that just fits `structure` on screen, above 1 if it fits at 1:1 with room to spare.
+    pub fn fit_zoom(structure: (Position, Rect)) -> f32 {
+        let (_, Rect { width, height }) = structure;
+        SCREEN_SIZE as f32 / (width.max(height) + 2.0 * CAMERA_FIT_PADDING)
+    }
+
+    /// Zooms out (never in past 1:1) until `structure` fits on screen and centers on it.
+    pub fn fit(&mut self, structure: (Position, Rect), bounds: (Position, Rect)) {
+        let (min, Rect { width, height }) = structure;
+        self.zoom_to(Self::fit_zoom(structure).clamp(MIN_ZOOM, 1.0));
+
+        let center = min + Vector::new(width / 2.0, height / 2.0);
+        self.drift_to(center + Vector::new(-self.bbox.width / 2.0, -self.bbox.height / 2.0), bounds);
+    }
+
+    pub const fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    pub fn toggle_lock(&mut self) {
+        self.locked = !self.locked;
+    }
+
+    pub const fn is_panning(&self) -> bool {
+        self.pan_origin.is_some()
+    }
+
+    /// Moves the frame by hand, without leaving `bounds`.
+    pub fn pan(&mut self, offset: Vector, bounds: (Position, Rect)) {
+        if self.pan_origin.is_none() {
+            self.pan_origin = Some(self.anchor_point);
+        }
+        self.mv(offset);
+        self.anchor_point = self.clamp_anchor(self.anchor_point, bounds);
+    }
+
+    /// Jumps back to where panning started.
+    pub fn stop_panning(&mut self) {
+        if let Some(origin) = self.pan_origin.take() {
+            self.anchor_point = origin;
+        }
+    }
+
+    /// Scrolls once `target` leaves the dead zone, far enough to bring it back to the edge.
+    pub fn follow(&mut self, target: Position, bounds: (Position, Rect)) {
+        // the dead zone is in pixels
+        let offset = Vector::between(self.anchor_point, target) * self.zoom;
+        let push = Vector::new(
+            offset.x - offset.x.clamp(CAMERA_DEAD_ZONE_MIN.x, CAMERA_DEAD_ZONE_MAX.x),
+            offset.y - offset.y.clamp(CAMERA_DEAD_ZONE_MIN.y, CAMERA_DEAD_ZONE_MAX.y),
+        );
+        self.drift_to(self.anchor_point + push / self.zoom, bounds);
+    }
+
+    /// Eases the anchor point towards `anchor_point`, keeping the frame inside `bounds`.
+    pub fn drift_to(&mut self, anchor_point: Position, bounds: (Position, Rect)) {
+        if self.locked || self.is_panning() {
+            return;
+        }
+        let target = self.clamp_anchor(anchor_point, bounds);
+        self.anchor_point = self.anchor_point + Vector::between(self.anchor_point, target) * CAMERA_LERP;
+    }
+
+    /// Shakes the screen and pauses the simulation for a moment, more for harder hits.
+    pub fn impact(&mut self, impulse: f32) {
+        if impulse >= SHAKE_IMPULSE {
+            self.shake = self.shake.max((impulse * SHAKE_PER_IMPULSE).min(MAX_SHAKE));
+        }
+        self.hit_stop = self.hit_stop.max(((impulse / HIT_STOP_IMPULSE) as u8).min(MAX_HIT_STOP));
+    }
+
+    /// Whether the simulation is paused by a hit-stop.
+    pub const fn is_stopped(&self) -> bool {
+        self.hit_stop > 0
+    }
+
+    /// Moves the shake to a new random offset and lets it and the hit-stop wear off.
+    pub fn step_effects(&mut self) {
+        self.shake_offset = Vector::noise(&mut self.seed) * (self.shake / self.zoom);
+        self.shake *= SHAKE_DECAY;
+        if self.shake < 0.5 {
+            self.shake = 0.0;
+        }
+        self.hit_stop = self.hit_stop.saturating_sub(1);
+    }
+
+    /// Where the frame is drawn from, shaken.
+    fn view_anchor(&self) -> Position {
+        self.anchor_point + self.shake_offset
+    }
+
+    pub fn from_px_to_units(&self, x: i32, y: i32) -> Position {
         let mut pos: Position = Default::default();
-        let frame_width = SCREEN_SIZE as f32;
+        let frame_width = self.bbox.width;
         let distance_from_anchor_x = (x as f32) / SCREEN_SIZE as f32 * frame_width;
         pos.x = distance_from_anchor_x + self.anchor_point.x;
 
-        let frame_height = SCREEN_SIZE as f32;
-        let distance_from_anchor_y = ((SCREEN_SIZE as u32 - y) as f32) / SCREEN_SIZE as f32 * frame_height;
+        let frame_height = self.bbox.height;
+        let distance_from_anchor_y = ((SCREEN_SIZE as i32 - y) as f32) / SCREEN_SIZE as f32 * frame_height;
         pos.y = distance_from_anchor_y + self.anchor_point.y;
         pos
     }
 
-    pub fn from_units_to_px(&self, pos: Position) -> (u32, u32) {
-        let distance_from_anchor_x = pos.x - self.anchor_point.x;
-        let frame_width = SCREEN_SIZE as f32;
-        let x = (SCREEN_SIZE as f32 * (distance_from_anchor_x / frame_width)) as u32;
+    /// Screen coordinates of `pos`, negative or past `SCREEN_SIZE` when it's off screen.
+    pub fn from_units_to_px(&self, pos: Position) -> (i32, i32) {
+        let anchor_point = self.view_anchor();
+        let distance_from_anchor_x = pos.x - anchor_point.x;
+        let frame_width = self.bbox.width;
+        let x = (SCREEN_SIZE as f32 * (distance_from_anchor_x / frame_width)).floor() as i32;
 
-        let distance_from_anchor_y = pos.y - self.anchor_point.y;
-        let frame_height = SCREEN_SIZE as f32;
-        let y = SCREEN_SIZE - (SCREEN_SIZE as f32 * (distance_from_anchor_y / frame_height)) as u32;
+        let distance_from_anchor_y = pos.y - anchor_point.y;
+        let frame_height = self.bbox.height;
+        let y = SCREEN_SIZE as i32 - (SCREEN_SIZE as f32 * (distance_from_anchor_y / frame_height)).floor() as i32;
         (x, y)
     }
 
@@ -67,13 +212,24 @@ impl Frame {
     //     }
     // }
 
+    /// Point `inset` pixels inside the edge of the screen on the way from its center to `target`,
+    /// for pointing at things that are off screen.
+    pub fn edge_point(&self, target: Position, inset: i32) -> (i32, i32) {
+        let (x, y) = self.from_units_to_px(target);
+        let half = SCREEN_SIZE as f32 / 2.0;
+        let offset = Vector::new(x as f32 - half, y as f32 - half);
+        let scale = ((half - inset as f32) / offset.x.abs().max(offset.y.abs())).min(1.0);
+        ((half + offset.x * scale) as i32, (half + offset.y * scale) as i32)
+    }
+
     pub fn get_bbox(&self) -> Rect {
         self.bbox
     }
 
-    pub fn drawing_coords(&self, position: Position, bbox: Rect) -> Option<(u32, u32)> {
-        if self.bbox.intersects(self.anchor_point, bbox, position) {
-            Some(self.from_units_to_px(position))
+    /// Screen coordinates of the top-left corner of `bbox` at `position`, if any of it is on screen.
+    pub fn drawing_coords(&self, position: Position, bbox: Rect) -> Option<(i32, i32)> {
+        if self.bbox.intersects(self.view_anchor(), bbox, position) {
+            Some(self.from_units_to_px(position + Vector::new(0.0, bbox.height)))
         } else {
             None
         }
@@ -95,4 +251,159 @@ mod tests {
 
         assert_eq!(pos, pos2);
     }
+
+    #[test]
+    fn test_off_screen_coords_are_negative() {
+        let frame = Frame::new(Position::new(0.0, 0.0));
+        assert_eq!(frame.from_units_to_px(Position::new(-4.0, 170.0)), (-4, -10));
+        assert_eq!(frame.from_units_to_px(Position::new(-0.5, -2.0)), (-1, 162));
+
+        // a box half off the left edge is still drawn, from where it starts
+        let coords = frame.drawing_coords(Position::new(-4.0, 0.0), Rect::new(8.0, 8.0));
+        assert_eq!(coords, Some((-4, 152)));
+    }
+
+    fn bounds() -> (Position, Rect) {
+        (Position::new(-100.0, -80.0), Rect::new(400.0, 400.0))
+    }
+
+    #[test]
+    fn test_follow_dead_zone() {
+        let mut frame = Frame::new(Position::new(0.0, 0.0));
+        let inside = Position::new(0.0, 0.0) + (CAMERA_DEAD_ZONE_MIN + CAMERA_DEAD_ZONE_MAX) * 0.5;
+        frame.follow(inside, bounds());
+        assert_eq!(frame.get_position(), Position::new(0.0, 0.0));
+
+        // the frame catches up a bit every frame
+        let ahead = Position::new(CAMERA_DEAD_ZONE_MAX.x + 50.0, inside.y);
+        frame.follow(ahead, bounds());
+        let first = frame.get_position().x;
+        assert!(first > 0.0 && first < 50.0);
+        for _ in 0..200 {
+            frame.follow(ahead, bounds());
+        }
+        assert!((frame.get_position().x - 50.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_frame_stays_in_bounds() {
+        let mut frame = Frame::new(Position::new(0.0, 0.0));
+        for _ in 0..200 {
+            frame.drift_to(Position::new(1000.0, -500.0), bounds());
+        }
+        // the right edge of the frame stops at the right edge of the bounds
+        assert!((frame.get_position().x - 140.0).abs() < 0.01);
+        assert!((frame.get_position().y + 80.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_zoomed_units_to_px() {
+        let mut frame = Frame::new(Position::new(0.0, 0.0));
+        frame.set_zoom(0.5);
+        assert_eq!(frame.from_units_to_px(Position::new(100.0, 100.0)), (50, 110));
+        assert_eq!(frame.from_px_to_units(50, 110), Position::new(100.0, 100.0));
+    }
+
+    #[test]
+    fn test_fit_zooms_out_for_large_structures() {
+        let mut frame = Frame::new(Position::new(0.0, 0.0));
+        let small = (Position::new(0.0, 0.0), Rect::new(60.0, 30.0));
+        for _ in 0..200 {
+            frame.fit(small, bounds());
+        }
+        assert!((frame.get_zoom() - 1.0).abs() < 0.001);
+
+        let large = (Position::new(-100.0, 0.0), Rect::new(320.0, 60.0));
+        let roomy = (Position::new(-300.0, -300.0), Rect::new(1000.0, 1000.0));
+        for _ in 0..200 {
+            frame.fit(large, roomy);
+        }
+        assert!(frame.get_zoom() < 0.5);
+        let (left, _) = frame.from_units_to_px(large.0);
+        let (right, _) = frame.from_units_to_px(large.0 + Vector::new(320.0, 0.0));
+        assert!(left > 0 && right < SCREEN_SIZE as i32);
+    }
+
+    #[test]
+    fn test_panning() {
+        let mut frame = Frame::new(Position::new(0.0, 0.0));
+        frame.pan(Vector::new(-30.0, 10.0), bounds());
+        assert_eq!(frame.get_position(), Position::new(-30.0, 10.0));
+
+        // no further than the bounds, and the frame doesn't drift while panned
+        frame.pan(Vector::new(-500.0, 0.0), bounds());
+        assert_eq!(frame.get_position(), Position::new(-100.0, 10.0));
+        frame.drift_to(Position::new(0.0, 0.0), bounds());
+        assert_eq!(frame.get_position(), Position::new(-100.0, 10.0));
+
+        frame.stop_panning();
+        assert!(!frame.is_panning());
+        assert_eq!(frame.get_position(), Position::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn test_shake_wears_off() {
+        let mut frame = Frame::new(Position::new(0.0, 0.0));
+        frame.impact(SHAKE_IMPULSE / 2.0);
+        frame.step_effects();
+        assert_eq!(frame.from_units_to_px(Position::new(10.0, 10.0)), (10, 150));
+
+        frame.impact(SHAKE_IMPULSE * 4.0);
+        frame.step_effects();
+        assert_ne!(frame.from_units_to_px(Position::new(10.0, 10.0)), (10, 150));
+        // the frame itself doesn't move and aiming isn't shaken
+        assert_eq!(frame.get_position(), Position::new(0.0, 0.0));
+        assert_eq!(frame.from_px_to_units(10, 150), Position::new(10.0, 10.0));
+
+        for _ in 0..60 {
+            frame.step_effects();
+        }
+        assert_eq!(frame.from_units_to_px(Position::new(10.0, 10.0)), (10, 150));
+    }
+
+    #[test]
+    fn test_hit_stop_scales_with_impulse() {
+        let mut frame = Frame::new(Position::new(0.0, 0.0));
+        frame.impact(HIT_STOP_IMPULSE / 2.0);
+        assert!(!frame.is_stopped());
+
+        let stopped_for = |impulse: f32| {
+            let mut frame = Frame::new(Position::new(0.0, 0.0));
+            frame.impact(impulse);
+            let mut frames = 0;
+            while frame.is_stopped() {
+                frame.step_effects();
+                frames += 1;
+            }
+            frames
+        };
+        assert_eq!(stopped_for(HIT_STOP_IMPULSE), 1);
+        assert!(stopped_for(HIT_STOP_IMPULSE * 3.0) > stopped_for(HIT_STOP_IMPULSE));
+        assert_eq!(stopped_for(f32::INFINITY), MAX_HIT_STOP);
+    }
+
+    #[test]
+    fn test_edge_point() {
+        let mut frame = Frame::new(Position::new(0.0, 0.0));
+        // straight right of the center, then up and to the left
+        assert_eq!(frame.edge_point(Position::new(400.0, 80.0), 2), (158, 80));
+        assert_eq!(frame.edge_point(Position::new(-80.0, 320.0), 2), (28, 2));
+
+        // the same spot is on screen once zoomed out
+        frame.set_zoom(0.25);
+        assert_eq!(frame.edge_point(Position::new(400.0, 80.0), 2), (100, 140));
+    }
+
+    #[test]
+    fn test_locked_frame_stays_put() {
+        let mut frame = Frame::new(Position::new(0.0, 0.0));
+        frame.toggle_lock();
+        frame.follow(Position::new(500.0, 0.0), bounds());
+        frame.drift_to(Position::new(-50.0, 0.0), bounds());
+        assert_eq!(frame.get_position(), Position::new(0.0, 0.0));
+
+        frame.toggle_lock();
+        frame.drift_to(Position::new(-50.0, 0.0), bounds());
+        assert!(frame.get_position().x < 0.0);
+    }
 }
\ No newline at end of file
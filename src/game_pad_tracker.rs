@@ -9,6 +9,8 @@ pub struct GamePadTracker {
     new_releases: u8,
     pub mouse_x: i16,
     pub mouse_y: i16,
+    prev_mouse_x: i16,
+    prev_mouse_y: i16,
 }
 
 impl GamePadTracker {
@@ -23,6 +25,8 @@ impl GamePadTracker {
             new_releases: 0,
             mouse_x: 0,
             mouse_y: 0,
+            prev_mouse_x: 0,
+            prev_mouse_y: 0,
         }
     }
 
@@ -36,10 +40,17 @@ impl GamePadTracker {
         self.new_clicks = self.current_mouse & (self.current_mouse ^ self.prev_mouse);
         self.new_releases = self.prev_mouse & (self.current_mouse ^ self.prev_mouse);
 
+        self.prev_mouse_x = self.mouse_x;
+        self.prev_mouse_y = self.mouse_y;
         self.mouse_x = mouse_x;
         self.mouse_y = mouse_y;
     }
 
+    /// How far the mouse moved since the last update, in pixels.
+    pub const fn mouse_delta(&self) -> (i16, i16) {
+        (self.mouse_x - self.prev_mouse_x, self.mouse_y - self.prev_mouse_y)
+    }
+
     pub const fn pressed(&self, key: u8) -> bool {
         self.current_gamepad & key != 0
     }
@@ -94,4 +105,12 @@ mod tests {
         assert!(inputs.newly_released(MOUSE_LEFT));
         assert!(!inputs.newly_released(MOUSE_RIGHT));
     }
+
+    #[test]
+    fn test_gamepad_tracker_mouse_delta() {
+        let mut inputs = GamePadTracker::new();
+        inputs.update(0, MOUSE_MIDDLE, 40, 60);
+        inputs.update(0, MOUSE_MIDDLE, 35, 70);
+        assert_eq!(inputs.mouse_delta(), (-5, 10));
+    }
 }
\ No newline at end of file
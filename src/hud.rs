@@ -0,0 +1,121 @@
+use std::fmt::{self, Write};
+use crate::vector::Vector;
+use crate::wasm4::*;
+use crate::palettes;
+
+
+/// Size of a glyph of the built-in font, in pixels.
+const FONT_SIZE: i32 = 8;
+
+/// A string that's formatted into a fixed array, so drawing numbers doesn't touch the heap.
+/// Anything past `N` bytes is cut off.
+pub struct TextBuffer<const N: usize> {
+    bytes: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> TextBuffer<N> {
+    pub const fn new() -> Self {
+        Self { bytes: [0; N], len: 0 }
+    }
+
+    pub fn as_str(&self) -> &str {
+        // strings are only ever cut between characters
+        std::str::from_utf8(&self.bytes[..self.len]).unwrap_or_default()
+    }
+}
+
+impl<const N: usize> Write for TextBuffer<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        // as much as fits, without splitting a character
+        let mut fits = s.len().min(N - self.len);
+        while !s.is_char_boundary(fits) {
+            fits -= 1;
+        }
+        self.bytes[self.len..self.len + fits].copy_from_slice(&s.as_bytes()[..fits]);
+        self.len += fits;
+        if fits < s.len() { Err(fmt::Error) } else { Ok(()) }
+    }
+}
+
+/// One line of text, as wide as the screen.
+type Line = TextBuffer<{ SCREEN_SIZE as usize / FONT_SIZE as usize }>;
+
+fn format(args: fmt::Arguments) -> Line {
+    let mut line = Line::new();
+    // a line that doesn't fit is drawn cut off
+    let _ = line.write_fmt(args);
+    line
+}
+
+/// Draws formatted text with its top-left corner at (`x`, `y`).
+pub fn print(args: fmt::Arguments, x: i32, y: i32) {
+    text(format(args).as_str(), x, y);
+}
+
+/// Draws formatted text ending at `right`.
+pub fn print_right(args: fmt::Arguments, right: i32, y: i32) {
+    let line = format(args);
+    text(line.as_str(), right - line.as_str().len() as i32 * FONT_SIZE, y);
+}
+
+/// Arrow at (`x`, `y`) pointing away from the center of the screen, towards
+/// something `distance` units off screen, with the distance written behind it.
+pub fn draw_indicator(x: i32, y: i32, distance: f32, colors: u16) {
+    let half = SCREEN_SIZE as f32 / 2.0;
+    let direction = Vector::new(x as f32 - half, y as f32 - half).normalize();
+    let across = Vector::new(-direction.y, direction.x);
+    let point = |offset: Vector| (x + offset.x.round() as i32, y + offset.y.round() as i32);
+
+    unsafe { *DRAW_COLORS = colors; }
+    for barb in [direction * -4.0 + across * 3.0, direction * -4.0 - across * 3.0, direction * -6.0] {
+        let (x2, y2) = point(barb);
+        line(x, y, x2, y2);
+    }
+
+    let hint = format(format_args!("{}", distance as u32));
+    let width = hint.as_str().len() as i32 * FONT_SIZE;
+    let (center_x, center_y) = point(direction * -(8.0 + width as f32 / 2.0));
+    let max = SCREEN_SIZE as i32 - FONT_SIZE;
+    text(hint.as_str(), (center_x - width / 2).clamp(0, max), (center_y - FONT_SIZE / 2).clamp(0, max));
+}
+
+/// What the player needs to know during a level, drawn over it in screen space.
+#[derive(Copy, Clone, Debug)]
+pub struct Hud {
+    /// starting from 1
+    pub level: u8,
+    pub cows: usize,
+    pub score: u32,
+    pub enemies: usize,
+}
+
+impl Hud {
+    pub fn draw(&self) {
+        let right = SCREEN_SIZE as i32 - 1;
+        unsafe { *DRAW_COLORS = palettes::LIGHT; }
+        print(format_args!("LEVEL {}", self.level), 1, 1);
+        print_right(format_args!("{}", self.score), right, 1);
+        print(format_args!("COWS {}", self.cows), 1, 2 + FONT_SIZE);
+        print_right(format_args!("ENEMIES {}", self.enemies), right, 2 + FONT_SIZE);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_numbers() {
+        assert_eq!(format(format_args!("COWS {}", 3)).as_str(), "COWS 3");
+        assert_eq!(format(format_args!("{}", 4_294_967_295u32)).as_str(), "4294967295");
+    }
+
+    #[test]
+    fn test_long_text_is_cut_off() {
+        let mut buffer = TextBuffer::<8>::new();
+        assert!(write!(buffer, "SCORE {}", 1234).is_err());
+        assert_eq!(buffer.as_str(), "SCORE 12");
+    }
+}
@@ -1,5 +1,5 @@
-use std::{fmt::Debug};
-use crate::constants::{TIME_STEP, BOUNCE_DAMPING_FACTOR, FRICTION_DAMPING_FACTOR, COLLISION_DAMPING_FACTOR, THRESHOLD_VELOCITY, GRAVITY};
+use std::{fmt::Debug, collections::VecDeque};
+use crate::constants::{TIME_STEP, BOUNCE_DAMPING_FACTOR, STATIC_FRICTION, KINETIC_FRICTION, COLLISION_DAMPING_FACTOR, THRESHOLD_VELOCITY, GRAVITY, ORIGIN, CONTACT_SLOP, GROUND_ANGULAR_DAMPING, SLEEP_VELOCITY, SLEEP_ANGULAR_VELOCITY, MAX_SETTLE_FRAMES, HIT_POINTS, IMPACT_DAMAGE_THRESHOLD, PATROL_WAKE_IMPULSE, TRAJECTORY_STEPS, TRAJECTORY_DOT_SPACING, LONGHORN_DASH_SPEED, DAIRY_SPLIT_ANGLE, CHOCOLATE_SLAM_SPEED, CHOCOLATE_SLAM_MASS, AMMO_SPACING, CAMERA_HOME_OFFSET, CAMERA_MARGIN, PAN_SPEED, PARTICLE_IMPULSE, MAX_BURST, SPLINTERS, INDICATOR_INSET};
 // use crate::drawable::Drawable;
 use crate::{vector::Vector};
 // use crate::Player::Player;
@@ -8,12 +8,21 @@ use crate::position::Position;
 // use crate::Player_status::PlayerTransition;
 use crate::game_pad_tracker::GamePadTracker;
 use crate::frame::Frame;
+use crate::broadphase::SweepAndPrune;
+use crate::sleep::Islands;
+use crate::slingshot::Slingshot;
+use crate::enemy::Behaviour;
+use crate::sprites::{self, Sprite};
+use crate::animation::{Animation, Animator};
+use crate::particles::{Particles, ParticleKind};
+use crate::hud::{self, Hud};
+use crate::palettes::{self, Theme, PaletteFade};
 // use crate::constants::TIME_STEP;
 use crate::wasm4::*;
 use serde::{Deserialize, Serialize};
 
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Avatar {
     Normal,
     Longhorn,
@@ -21,26 +30,52 @@ pub enum Avatar {
     Chocolate,
 }
 
+/// Each cow has one ability, used with `BUTTON_1` while in flight:
+/// longhorns dash forward, dairy cows split in three and chocolate cows slam down.
 impl Avatar {
     pub fn get_hitbox(&self) -> Rect {
-        // todo!();
         match self {
-            _ => Rect::new(8.0, 8.0)
+            Self::Normal => Rect::new(8.0, 8.0),
+            // long and low, horns first
+            Self::Longhorn => Rect::new(10.0, 7.0),
+            Self::Dairy => Rect::new(7.0, 7.0),
+            Self::Chocolate => Rect::new(9.0, 9.0),
         }
     }
 
     pub const fn get_mass(&self) -> f32 {
         match self {
-            _ => 10.0
+            Self::Normal => 10.0,
+            Self::Longhorn => 8.0,
+            Self::Dairy => 7.0,
+            Self::Chocolate => 14.0,
         }
     }
 
-    pub fn draw(&self, x: i32, y: i32) {
+    pub const fn sprite(&self) -> Sprite {
+        match self {
+            Self::Normal => sprites::NORMAL,
+            Self::Longhorn => sprites::LONGHORN,
+            Self::Dairy => sprites::DAIRY,
+            Self::Chocolate => sprites::CHOCOLATE,
+        }
+    }
 
+    pub fn flight_animation(&self) -> &'static Animation {
+        match self {
+            Self::Normal => &sprites::NORMAL_FLIGHT,
+            Self::Longhorn => &sprites::LONGHORN_FLIGHT,
+            Self::Dairy => &sprites::DAIRY_FLIGHT,
+            Self::Chocolate => &sprites::CHOCOLATE_FLIGHT,
+        }
+    }
+
+    pub fn draw(&self, x: i32, y: i32) {
+        self.sprite().draw(x, y);
     }
 }
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum EnemyAvatar {
     Farmer,
     Scarecrow,
@@ -59,9 +94,20 @@ impl EnemyAvatar {
             _ => 10.0
         }
     }
+
+    pub const fn sprite(&self) -> Sprite {
+        match self {
+            Self::Farmer => sprites::FARMER,
+            Self::Scarecrow => sprites::SCARECROW,
+        }
+    }
+
+    pub fn draw(&self, x: i32, y: i32) {
+        self.sprite().draw(x, y);
+    }
 }
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum LevelObjectKind {
     // Ground (implicit)
     Box,
@@ -74,16 +120,6 @@ pub enum LevelObjectKind {
 }
 
 impl LevelObjectKind {
-    const BOX_SPRITE: &'static [u8] = &[
-        0b11111111,
-        0b11000011,
-        0b10100101,
-        0b10011001,
-        0b10010001,
-        0b10100101,
-        0b11000011,
-        0b11111111,
-    ];
     pub fn get_hitbox(&self) -> Rect {
         match self {
             Self::Box => Rect { width: 8.0, height: 8.0 },
@@ -94,17 +130,19 @@ impl LevelObjectKind {
         }
     }
 
-    pub fn draw(&self, x: u32, y: u32) {
-        let Rect {width, height} = self.get_hitbox();
+    /// Draws the kind unrotated with its top-left corner at `x`, `y`,
+    /// using the animator's current frame if it's playing something.
+    pub fn draw(&self, x: i32, y: i32, animator: Animator) {
+        if let Some(sprite) = animator.current_sprite() {
+            sprite.draw(x, y);
+            return;
+        }
         match self {
-            Self::Box => {
-                // blit(Self::BOX_SPRITE, x as _, y as _,)
-                unsafe { *DRAW_COLORS = 0x42; }
-                blit(Self::BOX_SPRITE, x as _, y as _, width as _, height as _, BLIT_1BPP);
-                // rect(x as _, y as _, width as _, height as _);
-            },
-            Self::Player(avatar) => avatar.draw(x as _, y as _),
-            _ => {}
+            Self::Box => sprites::BOX.draw(x, y),
+            Self::Player(avatar) => avatar.draw(x, y),
+            Self::Log { is_vertical: true, length } => sprites::LOG_VERTICAL.draw_tiled(x, y, *length as _, true),
+            Self::Log { is_vertical: false, length } => sprites::LOG_HORIZONTAL.draw_tiled(x, y, *length as _, false),
+            Self::Enemy(e_avatar) => e_avatar.draw(x, y),
         }
     }
 
@@ -117,11 +155,62 @@ impl LevelObjectKind {
         }
     }
 
-    // pub fn sprite(&self) -> &[u8] {
-    //     match self {
+    /// How well the object shrugs off hits, damage is divided by this. Cows don't break.
+    pub const fn get_toughness(&self) -> f32 {
+        match self {
+            Self::Box => 4.0,
+            Self::Log { .. } => 8.0,
+            Self::Player(_) => f32::INFINITY,
+            Self::Enemy(EnemyAvatar::Farmer) => 3.0,
+            Self::Enemy(EnemyAvatar::Scarecrow) => 6.0,
+        }
+    }
+
+    /// Score for destroying the object.
+    pub const fn get_points(&self) -> u32 {
+        match self {
+            Self::Box => 10,
+            Self::Log { .. } => 20,
+            Self::Player(_) => 0,
+            Self::Enemy(EnemyAvatar::Farmer) => 100,
+            Self::Enemy(EnemyAvatar::Scarecrow) => 150,
+        }
+    }
+
+    /// Stand-in for the sprite when zoomed out, the hitbox filled with one color.
+    pub fn draw_glyph(&self, x: i32, y: i32, zoom: f32) {
+        let Rect { width, height } = self.get_hitbox();
+        unsafe {
+            *DRAW_COLORS = match self {
+                Self::Box | Self::Log { .. } => palettes::ACCENT,
+                Self::Player(_) => palettes::LIGHT,
+                Self::Enemy(_) => palettes::DARK,
+            };
+        }
+        rect(x, y, ((width * zoom) as u32).max(1), ((height * zoom) as u32).max(1));
+    }
+
+    /// Played where the object broke, after it's been taken out of the level.
+    pub fn break_animation(&self) -> Option<&'static Animation> {
+        match self {
+            Self::Box => Some(&sprites::BOX_BREAK),
+            _ => None,
+        }
+    }
+
+    /// Only logs can topple, everything else keeps its orientation.
+    pub const fn can_rotate(&self) -> bool {
+        matches!(self, Self::Log { .. })
+    }
 
-    //     }
-    // }
+    /// Moment of inertia of a solid rectangle about its center.
+    pub fn get_moment_of_inertia(&self) -> f32 {
+        if !self.can_rotate() {
+            return f32::INFINITY;
+        }
+        let Rect { width, height } = self.get_hitbox();
+        self.get_mass() * (width.powi(2) + height.powi(2)) / 12.0
+    }
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
@@ -129,53 +218,467 @@ pub struct LevelObject {
     kind: LevelObjectKind,
     position: Position,
     velocity: Vector,
+    /// counter-clockwise rotation about the center, in radians
+    #[serde(default)]
+    angle: f32,
+    #[serde(default)]
+    angular_velocity: f32,
+    #[serde(default = "default_hit_points")]
+    hit_points: f32,
+    /// only farmers can patrol, levels giving one to anything else are rejected
+    #[serde(default)]
+    behaviour: Behaviour,
+    #[serde(skip)]
+    destroyed: bool,
+    /// consecutive frames spent below the sleep thresholds
+    #[serde(skip)]
+    still_frames: u16,
+    #[serde(skip)]
+    asleep: bool,
+    /// added on top of the kind's mass, e.g. by a chocolate cow's slam
+    #[serde(skip)]
+    extra_mass: f32,
+    /// part of the next step already covered by `advance`
+    #[serde(skip)]
+    swept: f32,
+    #[serde(skip)]
+    animator: Animator,
 }
 
 impl LevelObject {
     pub const fn new(kind: LevelObjectKind, position: Position, velocity: Vector) -> Self {
-        Self { kind, position, velocity }
+        Self { kind, position, velocity, angle: 0.0, angular_velocity: 0.0, hit_points: HIT_POINTS, behaviour: Behaviour::Fixed, destroyed: false, still_frames: 0, asleep: false, extra_mass: 0.0, swept: 0.0, animator: Animator::new() }
+    }
+
+    pub fn get_mass(&self) -> f32 {
+        self.kind.get_mass() + self.extra_mass
+    }
+
+    pub const fn get_hit_points(&self) -> f32 {
+        self.hit_points
+    }
+
+    pub const fn is_destroyed(&self) -> bool {
+        self.destroyed
+    }
+
+    /// Hits that change the object's velocity by more than the threshold wear it down.
+    pub fn take_damage(&mut self, velocity_change: f32) {
+        let damage = (velocity_change - IMPACT_DAMAGE_THRESHOLD).max(0.0) / self.kind.get_toughness();
+        self.hit_points -= damage;
+        if self.hit_points <= 0.0 {
+            self.destroyed = true;
+        }
+    }
+
+    pub const fn is_asleep(&self) -> bool {
+        self.asleep
+    }
+
+    pub fn sleep(&mut self) {
+        self.asleep = true;
+        self.velocity = Vector::default();
+        self.angular_velocity = 0.0;
+    }
+
+    pub fn wake(&mut self) {
+        if self.asleep {
+            self.asleep = false;
+            self.still_frames = 0;
+        }
+    }
+
+    /// Moves the object so its center is at `center`.
+    pub fn set_center(&mut self, center: Position) {
+        let Rect { width, height } = self.kind.get_hitbox();
+        self.position = center + Vector::new(-width / 2.0, -height / 2.0);
+    }
+
+    pub fn set_velocity(&mut self, velocity: Vector) {
+        self.velocity = velocity;
+        self.wake();
+    }
+
+    /// Counts how long the object has been (nearly) motionless.
+    pub fn update_still_frames(&mut self) -> u16 {
+        if self.velocity.magnitude() < SLEEP_VELOCITY && self.angular_velocity.abs() < SLEEP_ANGULAR_VELOCITY {
+            self.still_frames = self.still_frames.saturating_add(1);
+        } else {
+            self.still_frames = 0;
+        }
+        self.still_frames
+    }
+
+    pub const fn with_behaviour(mut self, behaviour: Behaviour) -> Self {
+        self.behaviour = behaviour;
+        self
+    }
+
+    /// Patrolling farmers keep moving on their own, so they're left out when deciding what's at rest.
+    pub const fn is_patrolling(&self) -> bool {
+        self.can_patrol() && self.behaviour.is_patrol()
+    }
+
+    pub const fn can_patrol(&self) -> bool {
+        matches!(self.kind, LevelObjectKind::Enemy(EnemyAvatar::Farmer))
+    }
+
+    /// Lets enemies walk around, as long as they're standing on something.
+    pub fn update_behaviour(&mut self, hits: &[Hit]) {
+        if !self.is_patrolling() {
+            return;
+        }
+        if let Some(speed) = self.behaviour.steer(self.center(), hits) {
+            if self.velocity.y.abs() < SLEEP_VELOCITY {
+                self.set_velocity(Vector::new(speed, self.velocity.y));
+            }
+        }
+    }
+
+    /// Picks the animation for what the object is doing and advances it a frame.
+    pub fn update_animation(&mut self) {
+        let animation = match self.kind {
+            LevelObjectKind::Player(avatar) if !self.asleep && !self.is_grounded() => Some(avatar.flight_animation()),
+            LevelObjectKind::Enemy(EnemyAvatar::Farmer) if self.is_patrolling() => Some(&sprites::FARMER_WALK),
+            _ => None,
+        };
+        match animation {
+            Some(animation) => self.animator.play(animation),
+            None => self.animator.stop(),
+        }
+        self.animator.step();
+    }
+
+    pub const fn is_enemy(&self) -> bool {
+        matches!(self.kind, LevelObjectKind::Enemy(_))
+    }
+
+    pub const fn is_player(&self) -> bool {
+        matches!(self.kind, LevelObjectKind::Player(_))
     }
 
     pub fn draw(&self, frame: Frame) {
         // get position based on frame data
-        if let Some((x, y)) = frame.drawing_coords(self.position, self.kind.get_hitbox()) {
-            self.kind.draw(x, y);
+        let (position, bbox) = self.bounds();
+        if let Some((x, y)) = frame.drawing_coords(position, bbox) {
+            match self.kind {
+                LevelObjectKind::Log { .. } if self.is_rotated() => self.draw_log(frame),
+                kind if frame.get_zoom() < 1.0 => kind.draw_glyph(x, y, frame.get_zoom()),
+                kind => kind.draw(x, y, self.animator),
+            }
         }
     }
 
-    pub fn step_physics(&mut self, physics: Physics) {
+    /// Tilted logs can't use their sprite, so they're drawn as one line per unit of thickness along their length.
+    fn draw_log(&self, frame: Frame) {
+        let Rect { width, height } = self.kind.get_hitbox();
+        let center = self.center();
+        let (thickness, length, along) = if width < height {
+            (width, height, Vector::new(0.0, 1.0))
+        } else {
+            (height, width, Vector::new(1.0, 0.0))
+        };
+        let along = along.rotate(self.angle);
+        let across = Vector::new(-along.y, along.x);
+
+        unsafe { *DRAW_COLORS = palettes::ACCENT; }
+        for i in 0..thickness as i32 {
+            let offset = across * (i as f32 + 0.5 - thickness / 2.0);
+            let (x1, y1) = frame.from_units_to_px(center + offset + along * (-length / 2.0));
+            let (x2, y2) = frame.from_units_to_px(center + offset + along * (length / 2.0));
+            line(x1, y1, x2, y2);
+        }
+    }
+
+    /// Returns the hit if the object landed on the ground.
+    pub fn step_physics(&mut self, physics: Physics) -> Option<Hit> {
+        // only what's left of the step if a swept impact already moved the object
+        let time_step = TIME_STEP * (1.0 - self.swept);
+        self.swept = 0.0;
         self.velocity.x += physics.gravity.x * TIME_STEP;
         self.velocity.y += physics.gravity.y * TIME_STEP;
-        self.position.x += self.velocity.x * TIME_STEP;
-        self.position.y += self.velocity.y * TIME_STEP;
+        self.position.x += self.velocity.x * time_step;
+        self.position.y += self.velocity.y * time_step;
+
+        if self.kind.can_rotate() {
+            self.angle += self.angular_velocity * time_step;
+            return self.resolve_ground_contact(physics);
+        }
 
         // energy loss due to bouncing
+        let mut hit = None;
         if self.position.y <= 0.0 {
             self.position.y = 0.0;
             if self.velocity.y < 0.0 {
+                let landing_velocity = self.velocity.y;
                 self.velocity.y *= -(1.0 - physics.bounce_damping_factor);
                 if self.velocity.y < THRESHOLD_VELOCITY {
                     self.velocity.y = 0.0;
                 }
+                let point = Position::new(self.center().x, 0.0);
+                hit = Some(Hit { point, impulse: (self.velocity.y - landing_velocity) * self.get_mass(), kind: self.kind, other: None });
             }
         }
 
         // energy loss due to friction
+        if self.position.y <= 0.0 {
+            let normal_impulse = physics.gravity.y.abs() * TIME_STEP;
+            self.velocity.x = physics.apply_friction(self.velocity.x, normal_impulse);
+        }
+        hit
+    }
+
+    /// Ground contact for rotating bodies: the corners that sank into the ground
+    /// push back, which is what makes a log pivot and topple.
+    fn resolve_ground_contact(&mut self, physics: Physics) -> Option<Hit> {
+        let corners = self.corners();
+        let lowest = corners.iter().map(|corner| corner.y).fold(f32::INFINITY, f32::min);
+        if lowest > 0.0 {
+            return None;
+        }
+
+        self.position.y -= lowest;
+        let center = self.center();
+        let (left, right) = corners.iter()
+            .filter(|corner| corner.y <= lowest + CONTACT_SLOP)
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(left, right), corner| (left.min(corner.x), right.max(corner.x)));
+        // when a face is on the ground the push lands wherever it stops the fall and the spin
+        // together, so a log lying flat settles instead of rocking from one end onto the other.
+        // Past the ends of the face only the end that's coming down can be held up
+        let x = if self.velocity.y < 0.0 {
+            (center.x + self.kind.get_moment_of_inertia() * self.angular_velocity / (self.get_mass() * self.velocity.y)).clamp(left, right)
+        } else if self.angular_velocity > 0.0 {
+            left
+        } else {
+            right
+        };
+        let point = Position::new(x, 0.0);
+
+        let normal = Vector::new(0.0, 1.0);
+        let r = Vector::between(center, point);
+        let velocity = self.velocity_at(r);
+
+        // resting contacts only close at about a frame's worth of gravity, don't bounce those
+        let resting = -velocity.y < 2.0 * physics.gravity.magnitude() * TIME_STEP;
+        let collision = Collision {
+            obj1: (f32::INFINITY, Vector::default()),
+            obj2: (1.0 / self.inverse_mass_along(r, normal), velocity),
+            normal,
+            kind: if resting { CollisionKind::PerfectlyInelastic } else { CollisionKind::Damping(physics.bounce_damping_factor) },
+        };
+        let friction = collision.friction_impulse(physics.static_friction, physics.kinetic_friction);
+        let impulse = collision.impulse();
+        self.apply_impulse(normal * impulse + friction, r);
+        // a face resting on the ground also holds back what spin is left
+        if resting && left < right {
+            self.angular_velocity *= 1.0 - GROUND_ANGULAR_DAMPING;
+        }
+        Some(Hit { point, impulse, kind: self.kind, other: None })
     }
 
     pub fn bounce(&mut self, bounce_damping_factor: f32) {
         self.velocity.y = -self.velocity.y * (1.0 - bounce_damping_factor);
     }
 
+    pub fn is_rotated(&self) -> bool {
+        self.angle != 0.0
+    }
+
+    pub fn center(&self) -> Position {
+        let Rect { width, height } = self.kind.get_hitbox();
+        self.position + Vector::new(width / 2.0, height / 2.0)
+    }
+
+    pub fn corners(&self) -> [Position; 4] {
+        self.kind.get_hitbox().corners(self.position, self.angle)
+    }
+
+    /// Axis-aligned box around the object at its current angle.
+    pub fn bounds(&self) -> (Position, Rect) {
+        if self.is_rotated() {
+            Rect::bounding(self.corners())
+        } else {
+            (self.position, self.kind.get_hitbox())
+        }
+    }
+
+    /// Velocity of the point at `r` from the center.
+    pub fn velocity_at(&self, r: Vector) -> Vector {
+        self.velocity + Vector::new(-r.y, r.x) * self.angular_velocity
+    }
+
+    /// Inverse of the mass felt by an impulse along `normal` applied at `r` from the center.
+    pub fn inverse_mass_along(&self, r: Vector, normal: Vector) -> f32 {
+        1.0 / self.get_mass() + r.cross(normal).powi(2) / self.kind.get_moment_of_inertia()
+    }
+
+    pub fn apply_impulse(&mut self, impulse: Vector, r: Vector) {
+        self.velocity = self.velocity + impulse / self.get_mass();
+        if self.kind.can_rotate() {
+            self.angular_velocity += r.cross(impulse) / self.kind.get_moment_of_inertia();
+        }
+    }
+
     pub fn collides_with(&self, other: &Self) -> bool {
-        self.kind.get_hitbox().intersects(self.position, other.kind.get_hitbox(), other.position)
+        self.contact_with(other).is_some()
+    }
+
+    pub fn contact_with(&self, other: &Self) -> Option<Contact> {
+        if self.is_rotated() || other.is_rotated() {
+            self.kind.get_hitbox().oriented_contact(self.position, self.angle, other.kind.get_hitbox(), other.position, other.angle)
+        } else {
+            self.kind.get_hitbox().contact(self.position, other.kind.get_hitbox(), other.position)
+        }
+    }
+
+    pub const fn get_position(&self) -> Position {
+        self.position
     }
+
+    pub fn get_hitbox(&self) -> Rect {
+        self.kind.get_hitbox()
+    }
+
+    /// Whether the bottom of the object is touching the ground.
+    pub fn is_grounded(&self) -> bool {
+        self.bounds().0.y <= CONTACT_SLOP
+    }
+
+    /// Returns the hit if the objects were touching.
+    pub fn resolve_collision(&mut self, other: &mut Self, physics: Physics) -> Option<Hit> {
+        let contact = self.contact_with(other)?;
+
+        let impulse = self.collide(other, contact.normal, contact.point, physics);
+
+        // objects resting on the ground can't be pushed into it, and ones still asleep don't move at all
+        let pinned1 = self.asleep || (self.is_grounded() && contact.normal.y > 0.0);
+        let pinned2 = other.asleep || (other.is_grounded() && contact.normal.y < 0.0);
+        let (s1, s2) = contact.separation(
+            if pinned1 { f32::INFINITY } else { self.get_mass() },
+            if pinned2 { f32::INFINITY } else { other.get_mass() },
+        );
+
+        self.position = self.position + contact.normal * -s1;
+        other.position = other.position + contact.normal * s2;
+        Some(Hit { point: contact.point, impulse, kind: self.kind, other: Some(other.kind) })
+    }
+
+    /// Exchanges momentum at `point` along `normal`, which points from `self` towards `other`.
+    /// Hits away from the center of a rotating body also spin it. Returns the normal impulse.
+    pub fn collide(&mut self, other: &mut Self, normal: Vector, point: Position, physics: Physics) -> f32 {
+        let r1 = Vector::between(self.center(), point);
+        let r2 = Vector::between(other.center(), point);
+
+        // a patrolling enemy walking on or into sleeping objects leaves them be,
+        // like it would the ground, unless it hits them hard
+        let (still1, still2) = (self.asleep && other.is_patrolling(), other.asleep && self.is_patrolling());
+        if still1 || still2 {
+            let collision = self.collision_with(other, r1, r2, normal, physics, (still1, still2));
+            let j = collision.impulse();
+            if j < PATROL_WAKE_IMPULSE {
+                let impulse = normal * j + collision.friction_impulse(physics.static_friction, physics.kinetic_friction);
+                if still1 {
+                    other.apply_impulse(impulse, r2);
+                } else {
+                    self.apply_impulse(-impulse, r1);
+                }
+                return j;
+            }
+        }
+
+        self.wake();
+        other.wake();
+        let collision = self.collision_with(other, r1, r2, normal, physics, (false, false));
+        let friction = collision.friction_impulse(physics.static_friction, physics.kinetic_friction);
+        let j = collision.impulse();
+        let impulse = normal * j + friction;
+        self.apply_impulse(-impulse, r1);
+        other.apply_impulse(impulse, r2);
+
+        self.take_damage(j / self.get_mass());
+        other.take_damage(j / other.get_mass());
+        j
+    }
+
+    /// The contact points behave like two particles with the effective mass along the normal,
+    /// `fixed` ones can't be moved at all.
+    fn collision_with(&self, other: &Self, r1: Vector, r2: Vector, normal: Vector, physics: Physics, fixed: (bool, bool)) -> Collision {
+        let side = |obj: &Self, r: Vector, fixed: bool| if fixed {
+            (f32::INFINITY, Vector::default())
+        } else {
+            (1.0 / obj.inverse_mass_along(r, normal), obj.velocity_at(r))
+        };
+        Collision {
+            obj1: side(self, r1, fixed.0),
+            obj2: side(other, r2, fixed.1),
+            normal,
+            kind: CollisionKind::Damping(physics.bounce_damping_factor),
+        }
+    }
+
+    /// Speeds up in the direction of travel.
+    pub fn dash(&mut self, speed: f32) {
+        if self.velocity.magnitude() > 0.0 {
+            self.set_velocity(self.velocity + self.velocity.normalize() * speed);
+        }
+    }
+
+    /// Drops straight down, heavier than before.
+    pub fn slam(&mut self, speed: f32, extra_mass: f32) {
+        self.extra_mass += extra_mass;
+        self.set_velocity(Vector::new(0.0, -speed));
+    }
+
+    /// Two copies flying `angle` to either side, spaced out so they don't start overlapping.
+    /// A cow that isn't moving splits sideways.
+    pub fn split(&self, angle: f32) -> [Self; 2] {
+        let Rect { width, height } = self.kind.get_hitbox();
+        let across = if self.velocity.magnitude() > 0.0 {
+            Vector::new(-self.velocity.y, self.velocity.x).normalize()
+        } else {
+            Vector::new(1.0, 0.0)
+        };
+        let across = across * width.hypot(height);
+        [(angle, across), (-angle, -across)].map(|(angle, offset)| Self {
+            position: self.position + offset,
+            velocity: self.velocity.rotate(angle),
+            ..*self
+        })
+    }
+
+    /// Distance covered by the next call to `step_physics`.
+    pub fn next_displacement(&self, physics: Physics) -> Vector {
+        (self.velocity + physics.gravity * TIME_STEP) * TIME_STEP
+    }
+
+    /// Moves the object through `fraction` of the next step ahead of time,
+    /// `step_physics` then only covers the rest of it.
+    pub fn advance(&mut self, fraction: f32, physics: Physics) {
+        self.position = self.position + self.next_displacement(physics) * fraction;
+        self.swept = (self.swept + fraction).min(1.0);
+    }
+
+    /// Objects covering more than half their own size in a step can skip over thin objects.
+    pub fn is_fast(&self, physics: Physics) -> bool {
+        let Rect { width, height } = self.kind.get_hitbox();
+        self.next_displacement(physics).magnitude() > 0.5 * width.min(height)
+    }
+
+    pub fn time_of_impact(&self, other: &Self, physics: Physics) -> Option<Impact> {
+        let displacement = self.next_displacement(physics) - other.next_displacement(physics);
+        let ((self_position, self_bbox), (other_position, other_bbox)) = (self.bounds(), other.bounds());
+        self_bbox.sweep(self_position, displacement, other_bbox, other_position)
+    }
+}
+
+fn default_hit_points() -> f32 {
+    HIT_POINTS
 }
 
 #[derive(Copy, Clone, Debug)]
 pub struct Rect {
-    width: f32,
-    height: f32,
+    pub width: f32,
+    pub height: f32,
 }
 
 impl Rect {
@@ -192,22 +695,211 @@ impl Rect {
         && self_position.y + self.height > other_position.y
         && other_position.y + other.height > self_position.y
     }
+
+    /// Contact between two overlapping rects, resolved along the axis of least penetration.
+    /// The normal points from `self` towards `other`.
+    pub fn contact(&self, self_position: Position, other: Self, other_position: Position) -> Option<Contact> {
+        if !self.intersects(self_position, other, other_position) {
+            return None;
+        }
+
+        let right = self_position.x + self.width - other_position.x;
+        let left = other_position.x + other.width - self_position.x;
+        let up = self_position.y + self.height - other_position.y;
+        let down = other_position.y + other.height - self_position.y;
+
+        let (x_normal, x_depth) = if right < left { (Vector::new(1.0, 0.0), right) } else { (Vector::new(-1.0, 0.0), left) };
+        let (y_normal, y_depth) = if up < down { (Vector::new(0.0, 1.0), up) } else { (Vector::new(0.0, -1.0), down) };
+
+        let point = self.overlap_center(self_position, other, other_position);
+        if x_depth < y_depth {
+            Some(Contact { normal: x_normal, depth: x_depth, point })
+        } else {
+            Some(Contact { normal: y_normal, depth: y_depth, point })
+        }
+    }
+
+    /// Middle of the region where the two rects overlap, or of the edge they share if they only touch.
+    pub fn overlap_center(&self, self_position: Position, other: Self, other_position: Position) -> Position {
+        Position::new(
+            (self_position.x.max(other_position.x) + (self_position.x + self.width).min(other_position.x + other.width)) / 2.0,
+            (self_position.y.max(other_position.y) + (self_position.y + self.height).min(other_position.y + other.height)) / 2.0,
+        )
+    }
+
+    /// Corners of the rect with its bottom left at `position` once rotated by `angle` about its center.
+    pub fn corners(&self, position: Position, angle: f32) -> [Position; 4] {
+        let (hw, hh) = (self.width / 2.0, self.height / 2.0);
+        let center = position + Vector::new(hw, hh);
+        [
+            Vector::new(-hw, -hh),
+            Vector::new(hw, -hh),
+            Vector::new(hw, hh),
+            Vector::new(-hw, hh),
+        ].map(|corner| center + corner.rotate(angle))
+    }
+
+    /// Smallest axis-aligned rect containing all `points`, and its bottom left.
+    pub fn bounding(points: [Position; 4]) -> (Position, Self) {
+        let (mut min, mut max) = (points[0], points[0]);
+        for p in points.iter() {
+            min = Position::new(min.x.min(p.x), min.y.min(p.y));
+            max = Position::new(max.x.max(p.x), max.y.max(p.y));
+        }
+        (min, Self::new(max.x - min.x, max.y - min.y))
+    }
+
+    /// Separating axis test between two rotated rects. The contact point is the
+    /// average of the corners found inside the other rect.
+    pub fn oriented_contact(&self, self_position: Position, self_angle: f32, other: Self, other_position: Position, other_angle: f32) -> Option<Contact> {
+        let corners_a = self.corners(self_position, self_angle);
+        let corners_b = other.corners(other_position, other_angle);
+        let axes = [
+            Vector::new(1.0, 0.0).rotate(self_angle),
+            Vector::new(0.0, 1.0).rotate(self_angle),
+            Vector::new(1.0, 0.0).rotate(other_angle),
+            Vector::new(0.0, 1.0).rotate(other_angle),
+        ];
+        let project = |corners: &[Position; 4], axis: Vector| {
+            corners.iter().map(|c| axis.dot(Vector::new(c.x, c.y)))
+                .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), d| (lo.min(d), hi.max(d)))
+        };
+
+        let mut best: Option<(f32, Vector)> = None;
+        for axis in axes {
+            let (min_a, max_a) = project(&corners_a, axis);
+            let (min_b, max_b) = project(&corners_b, axis);
+            let depth = max_a.min(max_b) - min_a.max(min_b);
+            if depth <= 0.0 {
+                return None;
+            }
+            if best.is_none_or(|(best_depth, _)| depth < best_depth) {
+                // point the normal from self towards other
+                let normal = if min_b + max_b < min_a + max_a { -axis } else { axis };
+                best = Some((depth, normal));
+            }
+        }
+        let (depth, normal) = best?;
+
+        let inside = |p: &Position, rect: &Self, position: Position, angle: f32| {
+            let local = Vector::between(position + Vector::new(rect.width / 2.0, rect.height / 2.0), *p).rotate(-angle);
+            local.x.abs() <= rect.width / 2.0 && local.y.abs() <= rect.height / 2.0
+        };
+        let (mut sum, mut count) = (Vector::default(), 0.0);
+        for p in corners_a.iter().filter(|p| inside(p, &other, other_position, other_angle)) {
+            sum = sum + Vector::new(p.x, p.y);
+            count += 1.0;
+        }
+        for p in corners_b.iter().filter(|p| inside(p, self, self_position, self_angle)) {
+            sum = sum + Vector::new(p.x, p.y);
+            count += 1.0;
+        }
+        let point = if count > 0.0 {
+            Position::new(sum.x / count, sum.y / count)
+        } else {
+            let (a, b) = (Rect::bounding(corners_a), Rect::bounding(corners_b));
+            Position::new((a.0.x + a.1.width / 2.0 + b.0.x + b.1.width / 2.0) / 2.0, (a.0.y + a.1.height / 2.0 + b.0.y + b.1.height / 2.0) / 2.0)
+        };
+
+        Some(Contact { normal, depth, point })
+    }
+
+    /// Swept AABB test for `self` moving by `displacement` relative to `other`.
+    /// Rects that already overlap are left to `contact`.
+    pub fn sweep(&self, self_position: Position, displacement: Vector, other: Self, other_position: Position) -> Option<Impact> {
+        let axis = |d: f32, min_a: f32, len_a: f32, min_b: f32, len_b: f32| {
+            if d > 0.0 {
+                Some(((min_b - (min_a + len_a)) / d, (min_b + len_b - min_a) / d))
+            } else if d < 0.0 {
+                Some(((min_b + len_b - min_a) / d, (min_b - (min_a + len_a)) / d))
+            } else if min_a + len_a > min_b && min_b + len_b > min_a {
+                Some((f32::NEG_INFINITY, f32::INFINITY))
+            } else {
+                None
+            }
+        };
+
+        let (x_entry, x_exit) = axis(displacement.x, self_position.x, self.width, other_position.x, other.width)?;
+        let (y_entry, y_exit) = axis(displacement.y, self_position.y, self.height, other_position.y, other.height)?;
+
+        let entry = x_entry.max(y_entry);
+        if !(0.0..1.0).contains(&entry) || entry > x_exit.min(y_exit) {
+            return None;
+        }
+
+        let normal = if x_entry > y_entry {
+            Vector::new(displacement.x.signum(), 0.0)
+        } else {
+            Vector::new(0.0, displacement.y.signum())
+        };
+        Some(Impact { time: entry, normal })
+    }
+}
+
+/// First time (as a fraction of the step) two moving rects touch.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Impact {
+    pub time: f32,
+    pub normal: Vector,
+}
+
+/// Where two objects hit each other this frame and the normal impulse they exchanged.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Hit {
+    pub point: Position,
+    pub impulse: f32,
+    pub kind: LevelObjectKind,
+    /// what `kind` ran into, `None` for the ground
+    pub other: Option<LevelObjectKind>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Contact {
+    pub normal: Vector,
+    pub depth: f32,
+    pub point: Position,
+}
+
+impl Contact {
+    /// How far each object has to move along the normal to no longer overlap,
+    /// split by inverse mass (an infinite mass doesn't move at all).
+    pub fn separation(&self, m1: f32, m2: f32) -> (f32, f32) {
+        let (w1, w2) = (1.0 / m1, 1.0 / m2);
+        if w1 + w2 == 0.0 {
+            return (0.0, 0.0);
+        }
+        (self.depth * w1 / (w1 + w2), self.depth * w2 / (w1 + w2))
+    }
 }
 
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub struct Physics {
     gravity: Vector,
     bounce_damping_factor: f32,
-    friction_damping_factor: f32,
+    static_friction: f32,
+    kinetic_friction: f32,
     collision_damping_factor: f32,
 }
 
+impl Physics {
+    /// Coulomb friction on a tangential speed, given the normal impulse (per unit mass)
+    /// pressing the surfaces together during the step.
+    pub fn apply_friction(&self, tangential_speed: f32, normal_impulse: f32) -> f32 {
+        if tangential_speed.abs() <= self.static_friction * normal_impulse {
+            0.0
+        } else {
+            tangential_speed - tangential_speed.signum() * self.kinetic_friction * normal_impulse
+        }
+    }
+}
+
 impl Default for Physics {
     fn default() -> Self {
         Self {
             gravity: GRAVITY,
             bounce_damping_factor: BOUNCE_DAMPING_FACTOR,
-            friction_damping_factor: FRICTION_DAMPING_FACTOR,
+            static_friction: STATIC_FRICTION,
+            kinetic_friction: KINETIC_FRICTION,
             collision_damping_factor: COLLISION_DAMPING_FACTOR,
         }
     }
@@ -215,63 +907,322 @@ impl Default for Physics {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LevelData {
+    #[serde(skip)]
     number: u8,
+    slingshot: Slingshot,
+    /// cows waiting their turn, the front one is loaded at each reset
+    ammo: VecDeque<Avatar>,
     objects: Vec<LevelObject>,
+    #[serde(default)]
     physics: Physics,
+    /// hits this frame, both with the ground and between objects
+    #[serde(skip)]
+    hits: Vec<Hit>,
+    /// objects destroyed this frame
+    #[serde(skip)]
+    destroyed: Vec<LevelObject>,
+    #[serde(default)]
+    theme: Theme,
+    /// points for everything destroyed so far
+    #[serde(skip)]
+    score: u32,
+    /// broken objects playing their break animation, no longer part of the simulation
+    #[serde(skip)]
+    breaking: Vec<LevelObject>,
 }
 
 impl LevelData {
-    pub const fn new(number: u8, objects: Vec<LevelObject>, physics: Physics) -> Self {
-        Self { number, objects, physics }
+    pub const fn new(number: u8, slingshot: Slingshot, ammo: VecDeque<Avatar>, objects: Vec<LevelObject>, physics: Physics) -> Self {
+        Self { number, slingshot, ammo, objects, physics, theme: Theme::Mossy, hits: Vec::new(), destroyed: Vec::new(), score: 0, breaking: Vec::new() }
     }
 
     pub fn draw(&self, frame: Frame) {
-        for obj in self.objects.iter() {
+        for obj in self.objects.iter().chain(self.breaking.iter()) {
             obj.draw(frame);
         }
     }
 
-    pub fn step_physics(&mut self) {
+    pub fn step_animations(&mut self) {
         for obj in self.objects.iter_mut() {
-            obj.step_physics(self.physics);
+            obj.update_animation();
+        }
+        for obj in self.breaking.iter_mut() {
+            obj.animator.step();
         }
+        self.breaking.retain(|obj| !obj.animator.is_finished());
     }
 
-    pub fn load_levels() -> Vec<Vec<LevelObject>> {
-        serde_json::from_str(include_str!("levels.json")).unwrap()
+    /// Forgets last frame's hits and destroyed objects.
+    pub fn clear_events(&mut self) {
+        self.hits.clear();
+        self.destroyed.clear();
     }
 
-    pub fn get_mut_player_object(&mut self) -> Option<&mut LevelObject> {
-        self.objects.iter_mut().find(|obj| matches!(obj, LevelObject { kind: LevelObjectKind::Player(_), .. }))
+    pub fn step_physics(&mut self) {
+        for obj in self.objects.iter_mut().filter(|obj| !obj.is_asleep()) {
+            if let Some(hit) = obj.step_physics(self.physics) {
+                self.hits.push(hit);
+            }
+        }
+        // enemies react to everything that hit this frame, landings included
+        for obj in self.objects.iter_mut() {
+            obj.update_behaviour(&self.hits);
+        }
     }
 
-    pub fn get_player_object(&self) -> Option<&LevelObject> {
-        self.objects.iter().find(|obj| matches!(obj, LevelObject { kind: LevelObjectKind::Player(_), .. }))
+    /// Points at cows and enemies the frame doesn't show.
+    pub fn draw_indicators(&self, frame: Frame) {
+        for obj in self.objects.iter().filter(|obj| obj.is_player() || obj.is_enemy()) {
+            let (position, bbox) = obj.bounds();
+            if frame.drawing_coords(position, bbox).is_some() {
+                continue;
+            }
+            let (x, y) = frame.edge_point(obj.center(), INDICATOR_INSET);
+            let distance = Vector::between(frame.from_px_to_units(x, y), obj.center()).magnitude();
+            hud::draw_indicator(x, y, distance, if obj.is_player() { palettes::LIGHT } else { palettes::DARK });
+        }
     }
-}
 
-#[derive(Copy, Clone, Debug)]
-pub enum PlayerStatus {
-    Reset,
-    Held(Position),
-    Ballistic(Vector),
-}
+    /// Impulse of the hardest hit this frame.
+    pub fn heaviest_hit(&self) -> Option<f32> {
+        self.hits.iter().map(|hit| hit.impulse).max_by(f32::total_cmp)
+    }
 
-impl PlayerStatus {
-    pub fn apply_transition(&mut self, transition: PlayerTransition) {
-        match (&self, transition) {
-            (Self::Reset, PlayerTransition::Grabbed(pos)) => *self = Self::Held(pos),
-            (Self::Held(held_pos), PlayerTransition::Released(release_pos)) => *self = Self::Ballistic(Vector::between(release_pos, *held_pos)),
-            (Self::Ballistic(_), PlayerTransition::Reset) => *self = Self::Reset,
-            _ => {}
+    /// Dust where things hit the ground, milk where cows hit anything and splinters from broken logs.
+    pub fn spawn_particles(&self, particles: &mut Particles) {
+        for hit in self.hits.iter().filter(|hit| hit.impulse >= PARTICLE_IMPULSE) {
+            let count = ((hit.impulse / PARTICLE_IMPULSE) as usize).min(MAX_BURST);
+            let up = Vector::new(0.0, 1.0);
+            if hit.other.is_none() {
+                particles.burst(ParticleKind::Dust, hit.point, count, 10.0, up);
+            }
+            if matches!(hit.kind, LevelObjectKind::Player(_)) || matches!(hit.other, Some(LevelObjectKind::Player(_))) {
+                particles.burst(ParticleKind::Milk, hit.point, count, 30.0, up);
+            }
+        }
+        for obj in self.destroyed.iter().filter(|obj| matches!(obj.kind, LevelObjectKind::Log { .. })) {
+            particles.burst(ParticleKind::Splinter, obj.center(), SPLINTERS, 40.0, Vector::default());
         }
     }
-}
 
-#[derive(Copy, Clone, Debug)]
+    /// Calls `visit` with the positions `obj` would pass through, stepped with the same
+    /// integrator as the real flight. Stops at the first contact with the ground or another object.
+    pub fn predict_trajectory(&self, mut obj: LevelObject, mut visit: impl FnMut(Position)) {
+        for _ in 0..TRAJECTORY_STEPS {
+            let displacement = obj.next_displacement(self.physics);
+            if displacement.y < 0.0 && obj.bounds().0.y + displacement.y <= 0.0 {
+                return;
+            }
+
+            obj.step_physics(self.physics);
+            if self.objects.iter().any(|other| !other.is_player() && obj.collides_with(other)) {
+                return;
+            }
+            visit(obj.get_position());
+        }
+    }
+
+    /// Whether everything has come to rest.
+    pub fn is_settled(&self) -> bool {
+        self.objects.iter().all(|obj| obj.is_asleep() || obj.is_patrolling())
+    }
+
+    /// Moves fast objects up to the first thing they would hit during the next step
+    /// and resolves the hit there, so they can't tunnel through thin objects.
+    pub fn resolve_fast_impacts(&mut self) {
+        let physics = self.physics;
+        for i in 0..self.objects.len() {
+            if !self.objects[i].is_fast(physics) {
+                continue;
+            }
+
+            let obj = &self.objects[i];
+            let first_impact = self.objects.iter().enumerate()
+                .filter(|&(j, _)| j != i)
+                .filter_map(|(j, other)| obj.time_of_impact(other, physics).map(|impact| (j, impact)))
+                .min_by(|(_, a), (_, b)| a.time.total_cmp(&b.time));
+
+            if let Some((j, impact)) = first_impact {
+                let (obj, other) = if i < j {
+                    let (head, tail) = self.objects.split_at_mut(j);
+                    (&mut head[i], &mut tail[0])
+                } else {
+                    let (head, tail) = self.objects.split_at_mut(i);
+                    (&mut tail[0], &mut head[j])
+                };
+                obj.advance(impact.time, physics);
+                other.advance(impact.time, physics);
+                // the two now touch, the hit is in the middle of the edge they share
+                let ((position, bbox), (other_position, other_bbox)) = (obj.bounds(), other.bounds());
+                let point = bbox.overlap_center(position, other_bbox, other_position);
+                let impulse = obj.collide(other, impact.normal, point, physics);
+                self.hits.push(Hit { point, impulse, kind: obj.kind, other: Some(other.kind) });
+            }
+        }
+    }
+
+    pub fn load_levels() -> Vec<LevelData> {
+        Self::parse_levels(include_str!("levels.json"))
+    }
+
+    /// Panics on malformed levels, including behaviours an object can't act on.
+    fn parse_levels(json: &str) -> Vec<LevelData> {
+        let mut levels: Vec<LevelData> = serde_json::from_str(json).unwrap();
+        for (i, level) in levels.iter_mut().enumerate() {
+            level.number = i as _;
+            for obj in level.objects.iter() {
+                assert!(!obj.behaviour.is_patrol() || obj.can_patrol(), "level {}: only farmers can patrol", i + 1);
+            }
+        }
+        levels
+    }
+
+    pub fn get_mut_player_object(&mut self) -> Option<&mut LevelObject> {
+        self.objects.iter_mut().find(|obj| matches!(obj, LevelObject { kind: LevelObjectKind::Player(_), .. }))
+    }
+
+    pub fn get_player_object(&self) -> Option<&LevelObject> {
+        self.objects.iter().find(|obj| matches!(obj, LevelObject { kind: LevelObjectKind::Player(_), .. }))
+    }
+
+    /// Triggers the player's ability, see `Avatar`.
+    pub fn use_player_ability(&mut self) {
+        let player = match self.get_mut_player_object() {
+            Some(player) => player,
+            None => return,
+        };
+        match player.kind {
+            LevelObjectKind::Player(Avatar::Longhorn) => player.dash(LONGHORN_DASH_SPEED),
+            LevelObjectKind::Player(Avatar::Dairy) => {
+                let copies = player.split(DAIRY_SPLIT_ANGLE);
+                self.objects.extend(copies);
+            },
+            LevelObjectKind::Player(Avatar::Chocolate) => player.slam(CHOCOLATE_SLAM_SPEED, CHOCOLATE_SLAM_MASS),
+            _ => {},
+        }
+    }
+
+    /// Clears away the last cow (and any copies it split into) and puts the
+    /// next one from the queue in the slingshot, where it waits without falling.
+    pub fn load_next_player(&mut self) {
+        self.objects.retain(|obj| !obj.is_player());
+        if let Some(avatar) = self.ammo.pop_front() {
+            let mut player = LevelObject::new(LevelObjectKind::Player(avatar), ORIGIN, Vector::default());
+            player.set_center(self.slingshot.get_position());
+            player.sleep();
+            self.objects.push(player);
+        }
+    }
+
+    /// Box around the slingshot and every object.
+    pub fn structure_bounds(&self) -> (Position, Rect) {
+        let slingshot = self.slingshot.get_position();
+        let (mut min, mut max) = (Position::new(slingshot.x, 0.0), slingshot);
+        for obj in self.objects.iter() {
+            let (position, Rect { width, height }) = obj.bounds();
+            min = Position::new(min.x.min(position.x), min.y.min(position.y));
+            max = Position::new(max.x.max(position.x + width), max.y.max(position.y + height));
+        }
+        (min, Rect::new(max.x - min.x, max.y - min.y))
+    }
+
+    /// Anchor point of the frame when nothing is flying, for levels that fit on screen.
+    pub fn camera_home(&self) -> Position {
+        self.slingshot.get_position() + CAMERA_HOME_OFFSET
+    }
+
+    /// Area the frame may show: the starting view and the structure with a margin around it.
+    pub fn camera_bounds(&self) -> (Position, Rect) {
+        let (structure, Rect { width, height }) = self.structure_bounds();
+        let home = self.camera_home();
+        let min = Position::new((structure.x - CAMERA_MARGIN).min(home.x), (structure.y - CAMERA_MARGIN).min(home.y));
+        let max = Position::new(
+            (structure.x + width + CAMERA_MARGIN).max(home.x + SCREEN_SIZE as f32),
+            (structure.y + height + CAMERA_MARGIN).max(home.y + SCREEN_SIZE as f32),
+        );
+        (min, Rect::new(max.x - min.x, max.y - min.y))
+    }
+
+    /// Cows still waiting, lined up on the ground behind the slingshot.
+    pub fn draw_ammo(&self, frame: Frame) {
+        let mut x = self.slingshot.get_position().x - AMMO_SPACING;
+        for avatar in self.ammo.iter() {
+            let hitbox = avatar.get_hitbox();
+            x -= hitbox.width;
+            let position = Position::new(x, 0.0);
+            if let Some((x, y)) = frame.drawing_coords(position, hitbox) {
+                if frame.get_zoom() < 1.0 {
+                    LevelObjectKind::Player(*avatar).draw_glyph(x, y, frame.get_zoom());
+                } else {
+                    avatar.draw(x, y);
+                }
+            }
+            x -= AMMO_SPACING;
+        }
+    }
+
+    pub fn enemies_remaining(&self) -> usize {
+        self.objects.iter().filter(|obj| obj.is_enemy()).count()
+    }
+
+    /// Broken objects disappear, enemies removed this way count as defeated.
+    pub fn remove_destroyed(&mut self) {
+        for obj in self.objects.iter().filter(|obj| obj.destroyed) {
+            self.destroyed.push(*obj);
+            self.score += obj.kind.get_points();
+            if let Some(animation) = obj.kind.break_animation() {
+                let mut piece = *obj;
+                piece.animator.play(animation);
+                self.breaking.push(piece);
+            }
+        }
+        self.objects.retain(|obj| !obj.destroyed);
+        self.wake_touching_destroyed();
+    }
+
+    /// Wakes whatever was resting on or leaning against this frame's broken objects,
+    /// and in turn whatever rests on those, so nothing is left floating.
+    fn wake_touching_destroyed(&mut self) {
+        let slop = Vector::new(CONTACT_SLOP, CONTACT_SLOP);
+        let mut falling: Vec<(Position, Rect)> = self.destroyed.iter().map(LevelObject::bounds).collect();
+        while let Some((position, Rect { width, height })) = falling.pop() {
+            let grown = Rect::new(width + 2.0 * CONTACT_SLOP, height + 2.0 * CONTACT_SLOP);
+            for obj in self.objects.iter_mut().filter(|obj| obj.is_asleep()) {
+                let (obj_position, obj_bbox) = obj.bounds();
+                if grown.intersects(position + -slop, obj_bbox, obj_position) {
+                    obj.wake();
+                    falling.push((obj_position, obj_bbox));
+                }
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum PlayerStatus {
+    Reset,
+    Held(Position),
+    Ballistic(Vector),
+}
+
+impl PlayerStatus {
+    pub fn apply_transition(&mut self, transition: PlayerTransition) {
+        match (&self, transition) {
+            (Self::Reset, PlayerTransition::Grabbed(pos)) => *self = Self::Held(pos),
+            (Self::Held(_), PlayerTransition::Released(release_velocity)) => *self = Self::Ballistic(release_velocity),
+            (Self::Ballistic(_), PlayerTransition::Reset) => *self = Self::Reset,
+            _ => {}
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
 pub enum PlayerTransition {
     Grabbed(Position),
-    Released(Position),
+    /// carries the launch velocity
+    Released(Vector),
+    /// the avatar's mid-flight ability
+    Ability,
     Reset,
 }
 
@@ -280,90 +1231,238 @@ pub struct PlayingData {
     levels: Vec<LevelData>,
     current_level: usize,
     active_data: LevelData,
-    player_status: PlayerStatus
+    player_status: PlayerStatus,
+    /// the ability can be used once per shot
+    ability_used: bool,
+    /// frames since the cow was launched
+    flight_frames: u16,
+    /// score from the levels already cleared
+    banked_score: u32,
+    broadphase: SweepAndPrune,
+    islands: Islands,
 }
 
 impl PlayingData {
+    pub fn new(levels: Vec<LevelData>) -> Self {
+        let mut playing_data = Self {
+            active_data: levels[0].clone(),
+            levels,
+            current_level: 0,
+            player_status: PlayerStatus::Reset,
+            ability_used: false,
+            flight_frames: 0,
+            banked_score: 0,
+            broadphase: SweepAndPrune::new(),
+            islands: Islands::new(),
+        };
+        playing_data.reset_player();
+        playing_data
+    }
+
+    pub fn draw_slingshot(&self, frame: Frame) {
+        let slingshot = self.active_data.slingshot;
+        let player_center = match (self.player_status, self.active_data.get_player_object()) {
+            (PlayerStatus::Held(_), Some(player)) => player.center(),
+            _ => slingshot.get_position(),
+        };
+        slingshot.draw(frame, player_center);
+    }
+
+    /// Dotted path the player would take if let go at the mouse position.
+    pub fn draw_trajectory(&self, frame: Frame, gpt: GamePadTracker) {
+        if !matches!(self.player_status, PlayerStatus::Held(_)) {
+            return;
+        }
+        let mut player = match self.active_data.get_player_object() {
+            Some(player) => *player,
+            None => return,
+        };
+
+        let release_pos = frame.from_px_to_units(gpt.mouse_x as _, gpt.mouse_y as _);
+        player.set_velocity(self.active_data.slingshot.release_velocity(release_pos));
+
+        let Rect { width, height } = player.get_hitbox();
+        let mut step = 0;
+        unsafe { *DRAW_COLORS = palettes::LIGHT; }
+        self.active_data.predict_trajectory(player, |position| {
+            step += 1;
+            if step % TRAJECTORY_DOT_SPACING == 0 {
+                let (x, y) = frame.from_units_to_px(position + Vector::new(width / 2.0, height / 2.0));
+                rect(x, y, 1, 1);
+            }
+        });
+    }
+
     pub fn get_player_transition(&self, gpt: GamePadTracker, frame: Frame) -> Option<PlayerTransition> {
         match self.player_status {
             PlayerStatus::Reset if gpt.newly_clicked(MOUSE_LEFT) => Some(PlayerTransition::Grabbed(frame.from_px_to_units(gpt.mouse_x as _, gpt.mouse_y as _))),
-            PlayerStatus::Held(_) if gpt.newly_released(MOUSE_LEFT) => Some(PlayerTransition::Released(frame.from_px_to_units(gpt.mouse_x as _, gpt.mouse_y as _))),
-            PlayerStatus::Ballistic(_) if gpt.newly_pressed(BUTTON_2) => Some(PlayerTransition::Reset),
+            PlayerStatus::Held(_) if gpt.newly_released(MOUSE_LEFT) => {
+                let release_pos = frame.from_px_to_units(gpt.mouse_x as _, gpt.mouse_y as _);
+                Some(PlayerTransition::Released(self.active_data.slingshot.release_velocity(release_pos)))
+            },
+            // something that never quite comes to rest mustn't hold up the next shot forever
+            PlayerStatus::Ballistic(_) if gpt.newly_pressed(BUTTON_2) || self.active_data.is_settled() || self.flight_frames >= MAX_SETTLE_FRAMES => Some(PlayerTransition::Reset),
+            PlayerStatus::Ballistic(_) if gpt.newly_pressed(BUTTON_1) && !self.ability_used => Some(PlayerTransition::Ability),
             _ => None
         }
     }
 
-    pub fn update_after_transition(&mut self) {
+    pub fn update_after_transition(&mut self, transition: PlayerTransition) {
         if let Some(player) = self.active_data.get_mut_player_object() {
-            match self.player_status {
-                PlayerStatus::Ballistic(release_velocity) => player.velocity = release_velocity,
-                PlayerStatus::Reset => self.reset_player(),
+            match (self.player_status, transition) {
+                (PlayerStatus::Ballistic(_), PlayerTransition::Ability) => self.active_data.use_player_ability(),
+                (PlayerStatus::Ballistic(release_velocity), _) => player.set_velocity(release_velocity),
+                (PlayerStatus::Reset, _) => self.reset_player(),
                 _ => {},
             }
         }
     }
 
     pub fn reset_player(&mut self) {
-        if let (Some(active_player), Some(og_player)) = (self.active_data.get_mut_player_object(), self.levels[self.current_level].get_player_object()) {
-            *active_player = *og_player;
+        self.active_data.load_next_player();
+    }
+
+    /// Drags the held player towards the mouse, as far as the band stretches.
+    pub fn pull_player(&mut self, gpt: GamePadTracker, frame: Frame) {
+        if !matches!(self.player_status, PlayerStatus::Held(_)) {
+            return;
+        }
+        let pull = self.active_data.slingshot.clamp_pull(frame.from_px_to_units(gpt.mouse_x as _, gpt.mouse_y as _));
+        if let Some(player) = self.active_data.get_mut_player_object() {
+            player.set_center(pull);
+            player.sleep();
+        }
+    }
+
+    /// Follows the cow up close while it flies, otherwise shows the whole structure.
+    /// Dragging with the middle mouse button or holding the arrows looks around until the next aim.
+    pub fn update_camera(&self, frame: &mut Frame, gpt: GamePadTracker) {
+        // bounds come from the level as it was built, not wherever things flew off to
+        let level = &self.levels[self.current_level];
+        let bounds = level.camera_bounds();
+
+        if matches!(self.player_status, PlayerStatus::Held(_)) {
+            frame.stop_panning();
+        } else if let Some(offset) = Self::pan_offset(gpt) {
+            frame.pan(offset / frame.get_zoom(), bounds);
+        }
+
+        match (self.player_status, self.active_data.get_player_object()) {
+            (PlayerStatus::Ballistic(_), Some(player)) => {
+                frame.zoom_to(1.0);
+                frame.follow(player.center(), bounds);
+            },
+            // levels that fit on screen are shown from the slingshot, bigger ones zoomed out
+            _ if Frame::fit_zoom(level.structure_bounds()) >= 1.0 => {
+                frame.zoom_to(1.0);
+                frame.drift_to(level.camera_home(), bounds);
+            },
+            _ => frame.fit(level.structure_bounds(), bounds),
         }
     }
 
+    /// How far the player wants to look around this frame, in pixels.
+    fn pan_offset(gpt: GamePadTracker) -> Option<Vector> {
+        if gpt.clicked(MOUSE_MIDDLE) && !gpt.newly_clicked(MOUSE_MIDDLE) {
+            // drag the level along with the mouse
+            let (dx, dy) = gpt.mouse_delta();
+            return Some(Vector::new(-dx as f32, dy as f32));
+        }
+        let mut offset = Vector::default();
+        for (button, direction) in [(BUTTON_LEFT, Vector::new(-1.0, 0.0)), (BUTTON_RIGHT, Vector::new(1.0, 0.0)), (BUTTON_DOWN, Vector::new(0.0, -1.0)), (BUTTON_UP, Vector::new(0.0, 1.0))] {
+            if gpt.pressed(button) {
+                offset = offset + direction * PAN_SPEED;
+            }
+        }
+        if offset == Vector::default() { None } else { Some(offset) }
+    }
+
     pub fn restart_level(&mut self) {
-        if self.current_level > self.levels.len() {
+        if self.current_level < self.levels.len() {
             self.active_data = self.levels[self.current_level].clone();
+            self.player_status = PlayerStatus::Reset;
+            self.reset_player();
         }
     }
 
     pub fn apply_transition(&mut self, transition: PlayerTransition) {
+        if let (PlayerStatus::Held(_), PlayerTransition::Released(_)) = (self.player_status, transition) {
+            self.ability_used = false;
+            self.flight_frames = 0;
+        }
+        if let PlayerTransition::Ability = transition {
+            self.ability_used = true;
+        }
         self.player_status.apply_transition(transition);
     }
 
     pub fn next_level(&mut self) {
+        self.banked_score += self.active_data.score;
         self.current_level += 1;
         self.restart_level();
     }
 
-    pub fn update_collisions(&mut self) -> LevelStatus {
-        // todo!();
-        let objects = &mut self.active_data.objects;
-        for i in 0..objects.len() {
-            for j in (i + 1)..objects.len() {
-                unsafe {
-                    let obj1 = objects.get_unchecked(i);
-                    let obj2 = objects.get_unchecked(j);
-
-                    // let dot1 = Vector::between(obj1.position, obj2.position).normalize().dot(obj1.velocity.normalize()).abs();
-                    // let b1 = 0.0 < dot1 && dot1 < 1.0;
+    /// Cows that haven't been launched yet, including the one in the slingshot.
+    pub fn cows_remaining(&self) -> usize {
+        let loaded = !matches!(self.player_status, PlayerStatus::Ballistic(_)) && self.active_data.get_player_object().is_some();
+        self.active_data.ammo.len() + usize::from(loaded)
+    }
 
-                    // let dot2 = Vector::between(obj2.position, obj1.position).normalize().dot(obj2.velocity.normalize()).abs();
-                    // let b2 = 0.0 < dot2 && dot2 < 1.0;
+    pub fn get_score(&self) -> u32 {
+        self.banked_score + self.active_data.score
+    }
 
-                    if obj1.collides_with(obj2) /* && (b1 || b2 ) */ {
-                        // update obj1
-                        let (m1, v1) = (obj1.kind.get_mass(), obj1.velocity);
+    pub fn hud(&self) -> Hud {
+        Hud {
+            level: self.active_data.number + 1,
+            cows: self.cows_remaining(),
+            score: self.get_score(),
+            enemies: self.active_data.enemies_remaining(),
+        }
+    }
 
-                        // update obj2
-                        let (m2, v2) = (obj2.kind.get_mass(), obj2.velocity);
+    pub fn is_final_level(&self) -> bool {
+        self.current_level + 1 >= self.levels.len()
+    }
 
-                        let collision = Collision {
-                            obj1: (m1, v1),
-                            obj2: (m2, v2),
-                            kind: CollisionKind::Damping(self.active_data.physics.bounce_damping_factor),
-                        };
+    pub fn level_status(&self) -> LevelStatus {
+        if self.active_data.enemies_remaining() == 0 {
+            LevelStatus::Complete
+        } else if self.active_data.get_player_object().is_none() && matches!(self.player_status, PlayerStatus::Reset) {
+            // the last cow has been launched and there was none left to load
+            LevelStatus::Lost
+        } else {
+            LevelStatus::InProgress
+        }
+    }
 
-                        let (v1_f, v2_f) = collision.simulate();
+    pub fn update_collisions(&mut self) -> LevelStatus {
+        self.active_data.clear_events();
+        self.active_data.resolve_fast_impacts();
 
-                        objects.get_unchecked_mut(i).velocity = v1_f;
-                        objects.get_unchecked_mut(j).velocity = v2_f;
-                    }
-                }
+        let objects = &mut self.active_data.objects;
+        self.islands.reset(objects.len());
+        for &(i, j) in self.broadphase.update(objects) {
+            // i < j, so the pair can be borrowed mutably at the same time
+            let (head, tail) = objects.split_at_mut(j);
+            let (obj1, obj2) = (&mut head[i], &mut tail[0]);
+            if obj1.is_asleep() && obj2.is_asleep() {
+                continue;
+            }
+            if let Some(hit) = obj1.resolve_collision(obj2, self.active_data.physics) {
+                self.islands.union(i, j);
+                self.active_data.hits.push(hit);
             }
         }
-        LevelStatus::InProgress
+        self.islands.update_sleep(objects);
+        self.active_data.remove_destroyed();
+        self.level_status()
     }
 
     pub fn step_physics(&mut self) {
+        if let PlayerStatus::Ballistic(_) = self.player_status {
+            self.flight_frames = self.flight_frames.saturating_add(1);
+        }
         self.active_data.step_physics();
     }
 }
@@ -371,7 +1470,7 @@ impl PlayingData {
 #[derive(Clone, Debug)]
 pub enum GameMode {
     TitleScreen,
-    Playing(PlayingData),
+    Playing(Box<PlayingData>),
     EndGame,
 }
 
@@ -383,24 +1482,31 @@ pub enum LevelStatus {
 }
 
 impl GameMode {
-    pub fn draw(&self, frame: Frame) {
+    pub fn draw(&self, frame: Frame, gpt: GamePadTracker, particles: &Particles) {
         match self {
             Self::TitleScreen => Self::draw_title(),
-            Self::Playing(PlayingData { active_data, .. }) => Self::draw_level(frame, active_data),
+            Self::Playing(playing_data) => Self::draw_level(frame, gpt, playing_data, particles),
             Self::EndGame => Self::draw_end_game(),
         }
     }
 
     fn draw_title() {
+        unsafe { *DRAW_COLORS = palettes::LIGHT; }
         text("Press X to play", 30, 50);
     }
 
-    fn draw_level(frame: Frame, level_data: &LevelData) {
-        // write level number in corner
-        level_data.draw(frame);
+    fn draw_level(frame: Frame, gpt: GamePadTracker, playing_data: &PlayingData, particles: &Particles) {
+        playing_data.draw_slingshot(frame);
+        playing_data.active_data.draw_ammo(frame);
+        playing_data.active_data.draw(frame);
+        particles.draw(frame);
+        playing_data.active_data.draw_indicators(frame);
+        playing_data.draw_trajectory(frame, gpt);
+        playing_data.hud().draw();
     }
 
     fn draw_end_game() {
+        unsafe { *DRAW_COLORS = palettes::LIGHT; }
         text("Congrats! You won!", 30, 50);
     }
 
@@ -430,13 +1536,20 @@ impl GameMode {
             if let Some(transition) = playing_data.get_player_transition(gpt, frame) {
                 // apply transition
                 playing_data.apply_transition(transition);
-                playing_data.update_after_transition();
+                playing_data.update_after_transition(transition);
+            }
+            playing_data.pull_player(gpt, frame);
+            // a hit-stop freezes the simulation for a moment, without replaying the hits that caused it
+            if frame.is_stopped() {
+                playing_data.active_data.clear_events();
+                return;
             }
             // check for collisions on all objects
             let level_status = playing_data.update_collisions();
     
             // check for complete/win condition
             match level_status {
+                LevelStatus::Complete if playing_data.is_final_level() => *self = Self::EndGame,
                 LevelStatus::Complete => playing_data.next_level(),
                 LevelStatus::Lost => playing_data.restart_level(),
                 LevelStatus::InProgress => playing_data.step_physics(),
@@ -444,26 +1557,44 @@ impl GameMode {
         }
     }
 
+    fn update_camera(&self, frame: &mut Frame, gpt: GamePadTracker) {
+        if let Self::Playing(playing_data) = self {
+            playing_data.update_camera(frame, gpt);
+        }
+    }
+
+    fn step_animations(&mut self) {
+        if let Self::Playing(playing_data) = self {
+            playing_data.active_data.step_animations();
+        }
+    }
+
+    fn theme(&self) -> Theme {
+        match self {
+            Self::Playing(playing_data) => playing_data.active_data.theme,
+            _ => Theme::Mossy,
+        }
+    }
+
+    fn heaviest_hit(&self) -> Option<f32> {
+        match self {
+            Self::Playing(playing_data) => playing_data.active_data.heaviest_hit(),
+            _ => None,
+        }
+    }
+
+    fn spawn_particles(&self, particles: &mut Particles) {
+        if let Self::Playing(playing_data) = self {
+            playing_data.active_data.spawn_particles(particles);
+        }
+    }
+
     fn start_game(&mut self) {
-        let level_objects = LevelData::load_levels();
-        let physics: Physics = Default::default();
-        let levels: Vec<_> = level_objects.into_iter().enumerate().map(|(i, objs)| {
-            LevelData {
-                number: i as _,
-                objects: objs,
-                physics: physics
-            }
-        }).collect();
-        let current_level = 0;
-        if current_level > levels.len() {
+        let levels = LevelData::load_levels();
+        if levels.is_empty() {
             *self = Self::EndGame
         } else {
-            *self = Self::Playing(PlayingData {
-                active_data: levels[current_level].clone(),
-                levels,
-                current_level,
-                player_status: PlayerStatus::Reset
-            })
+            *self = Self::Playing(Box::new(PlayingData::new(levels)))
         }
     }
 }
@@ -473,18 +1604,37 @@ pub struct GameState {
     pub gpt: GamePadTracker,
     pub frame: Frame,
     pub mode: GameMode,
+    pub particles: Particles,
+    pub palette: PaletteFade,
 }
 
 impl GameState {
     pub fn draw(&self) {
-        self.mode.draw(self.frame);
+        unsafe { *PALETTE = self.palette.current(); }
+        self.mode.draw(self.frame, self.gpt, &self.particles);
     }
 
     pub fn update(&mut self) {
         unsafe {
             self.gpt.update(*GAMEPAD1, *MOUSE_BUTTONS, *MOUSE_X, *MOUSE_Y);
         }
+        if self.gpt.newly_clicked(MOUSE_RIGHT) {
+            self.frame.toggle_lock();
+        }
+        let stopped = self.frame.is_stopped();
         self.mode.update(self.gpt, self.frame);
+        self.mode.update_camera(&mut self.frame, self.gpt);
+        if !stopped {
+            self.mode.step_animations();
+            self.mode.spawn_particles(&mut self.particles);
+            self.particles.step(GRAVITY);
+        }
+        self.frame.step_effects();
+        if let Some(impulse) = self.mode.heaviest_hit() {
+            self.frame.impact(impulse);
+        }
+        self.palette.fade_to(self.mode.theme().colors());
+        self.palette.step();
     }
 }
 
@@ -499,37 +1649,67 @@ pub enum CollisionKind {
 pub struct Collision {
     pub obj1: (f32, Vector),
     pub obj2: (f32, Vector),
+    /// unit contact normal pointing from obj1 towards obj2
+    pub normal: Vector,
     pub kind: CollisionKind,
 }
 
+impl CollisionKind {
+    pub const fn restitution(&self) -> f32 {
+        match self {
+            Self::Elastic => 1.0,
+            Self::Damping(factor) => 1.0 - *factor,
+            Self::PerfectlyInelastic => 0.0,
+        }
+    }
+}
+
 impl Collision {
-    pub fn simulate(&self) -> (Vector, Vector) {
+    /// Magnitude of the impulse exchanged along the normal (zero if the objects are separating).
+    pub fn impulse(&self) -> f32 {
         let (m1, v1) = self.obj1;
         let (m2, v2) = self.obj2;
-        match self.kind {
-            CollisionKind::PerfectlyInelastic => {
-                let p1 = m1 * v1;
-                let p2 = m2 * v2;
-
-                let v_f = (p1 + p2) / (m1 + m2);
 
-                (v_f, v_f)
-            },
-            kind => {
-                let factor = if let CollisionKind::Damping(factor) = kind { factor } else { 0.0 };
-                let p1 = m1 * v1;
-                let p2 = m2 * v2;
+        let approach_speed = (v1 - v2).dot(self.normal);
+        if approach_speed <= 0.0 {
+            return 0.0;
+        }
 
-                let mut v1_f = p2 / m1;
-                let mut v2_f = p1 / m2;
+        (1.0 + self.kind.restitution()) * approach_speed / (1.0 / m1 + 1.0 / m2)
+    }
 
-                // only dampen x velocity (todo: change this to only dampen in any direction where they are colliding)
-                v1_f.x *= 1.0 - factor;
-                v2_f.x *= 1.0 - factor;
+    /// Tangential impulse that friction applies to obj2 (obj1 receives the opposite).
+    /// Sticks if static friction can stop the sliding, otherwise slides with kinetic friction.
+    pub fn friction_impulse(&self, static_friction: f32, kinetic_friction: f32) -> Vector {
+        let (m1, v1) = self.obj1;
+        let (m2, v2) = self.obj2;
 
-                (v1_f, v2_f)
-            },
+        let relative = v1 - v2;
+        let sliding = relative - self.normal * relative.dot(self.normal);
+        let speed = sliding.magnitude();
+        if speed == 0.0 {
+            return Vector::default();
         }
+
+        let normal_impulse = self.impulse();
+        let sticking_impulse = speed / (1.0 / m1 + 1.0 / m2);
+        let j = if sticking_impulse <= static_friction * normal_impulse {
+            sticking_impulse
+        } else {
+            kinetic_friction * normal_impulse
+        };
+
+        sliding / speed * j
+    }
+
+    /// Final velocities after the impulse is applied. Only the components along the
+    /// normal change; tangential motion is left alone.
+    pub fn simulate(&self) -> (Vector, Vector) {
+        let (m1, v1) = self.obj1;
+        let (m2, v2) = self.obj2;
+        let j = self.impulse();
+
+        (v1 - self.normal * (j / m1), v2 + self.normal * (j / m2))
     }
 }
 
@@ -537,10 +1717,403 @@ impl Collision {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::constants::SLEEP_FRAMES;
+    use crate::enemy::Patrol;
 
     #[test]
     fn test_load_levels() {
-        LevelData::load_levels();
+        let levels = LevelData::load_levels();
+        assert_eq!(levels[1].theme, Theme::Icy);
+    }
+
+    fn test_playing_data() -> PlayingData {
+        let objects = vec![
+            LevelObject::new(LevelObjectKind::Enemy(EnemyAvatar::Farmer), Position::new(40.0, 0.0), Vector::new(0.0, 0.0)),
+        ];
+        let slingshot = Slingshot::new(Position::new(-60.0, 12.0), 24.0, 80.0, 1.5);
+        let ammo = VecDeque::from([Avatar::Normal; 3]);
+        let level = LevelData::new(0, slingshot, ammo, objects, Default::default());
+        PlayingData::new(vec![level.clone(), LevelData { number: 1, ..level }])
+    }
+
+    #[test]
+    fn test_level_in_progress() {
+        let playing_data = test_playing_data();
+        assert!(matches!(playing_data.level_status(), LevelStatus::InProgress));
+    }
+
+    #[test]
+    fn test_level_complete_when_enemies_defeated() {
+        let mut playing_data = test_playing_data();
+        let player = playing_data.active_data.get_mut_player_object().unwrap();
+        player.set_velocity(Vector::new(80.0, 0.0));
+        let next_to_player = player.position + Vector::new(7.0, 0.0);
+        let enemy = playing_data.active_data.objects.iter_mut().find(|obj| obj.is_enemy()).unwrap();
+        enemy.position = next_to_player;
+
+        assert!(matches!(playing_data.update_collisions(), LevelStatus::Complete));
+        assert_eq!(playing_data.active_data.enemies_remaining(), 0);
+    }
+
+    #[test]
+    fn test_resting_contact_does_no_damage() {
+        let mut playing_data = test_playing_data();
+        playing_data.active_data.objects = vec![
+            LevelObject::new(LevelObjectKind::Box, Position::new(0.0, 0.0), Vector::new(0.0, 0.0)),
+            LevelObject::new(LevelObjectKind::Box, Position::new(1.0, 8.0), Vector::new(0.0, 0.0)),
+        ];
+        for _ in 0..120 {
+            playing_data.update_collisions();
+            playing_data.step_physics();
+        }
+
+        assert_eq!(playing_data.active_data.objects.len(), 2);
+        assert!(playing_data.active_data.objects.iter().all(|obj| obj.hit_points == HIT_POINTS));
+    }
+
+    #[test]
+    fn test_hard_hit_breaks_box() {
+        let physics: Physics = Default::default();
+        let mut player = LevelObject::new(LevelObjectKind::Player(Avatar::Normal), Position::new(0.0, 0.0), Vector::new(60.0, 0.0));
+        let mut box_ = LevelObject::new(LevelObjectKind::Box, Position::new(7.0, 0.0), Vector::new(0.0, 0.0));
+
+        let contact = player.contact_with(&box_).unwrap();
+        player.collide(&mut box_, contact.normal, contact.point, physics);
+
+        assert!(box_.is_destroyed());
+        assert!(!player.is_destroyed());
+    }
+
+    #[test]
+    fn test_broken_box_animates_then_disappears() {
+        let mut level = test_playing_data().active_data;
+        let mut box_ = LevelObject::new(LevelObjectKind::Box, Position::new(0.0, 0.0), Vector::new(0.0, 0.0));
+        box_.take_damage(f32::INFINITY);
+        level.objects.push(box_);
+
+        level.remove_destroyed();
+        assert!(level.objects.iter().all(|obj| !obj.is_destroyed()));
+        assert_eq!(level.breaking.len(), 1);
+
+        for _ in 0..sprites::BOX_BREAK.duration() {
+            assert_eq!(level.breaking.len(), 1);
+            level.step_animations();
+        }
+        assert!(level.breaking.is_empty());
+    }
+
+    #[test]
+    fn test_hud_counts() {
+        let hud = test_playing_data().hud();
+        assert_eq!((hud.level, hud.cows, hud.score, hud.enemies), (1, 3, 0, 1));
+
+        let mut playing_data = launched(Avatar::Normal);
+        assert_eq!(playing_data.cows_remaining(), 2);
+
+        let enemy = playing_data.active_data.objects.iter_mut().find(|obj| obj.is_enemy()).unwrap();
+        enemy.take_damage(f32::INFINITY);
+        assert!(matches!(playing_data.update_collisions(), LevelStatus::Complete));
+        playing_data.next_level();
+
+        let hud = playing_data.hud();
+        assert_eq!((hud.level, hud.cows, hud.score, hud.enemies), (2, 3, 100, 1));
+    }
+
+    #[test]
+    fn test_hits_spawn_particles() {
+        let mut playing_data = test_playing_data();
+        let mut particles = Particles::new();
+        playing_data.active_data.objects = vec![
+            LevelObject::new(LevelObjectKind::Box, Position::new(0.0, 0.5), Vector::new(0.0, -1.0)),
+        ];
+        playing_data.update_collisions();
+        playing_data.step_physics();
+        playing_data.active_data.spawn_particles(&mut particles);
+        assert_eq!(particles.count(), 0);
+
+        // the cow lands hard enough for dust and milk
+        playing_data.active_data.objects = vec![
+            LevelObject::new(LevelObjectKind::Player(Avatar::Normal), Position::new(0.0, 0.5), Vector::new(0.0, -40.0)),
+        ];
+        playing_data.update_collisions();
+        playing_data.step_physics();
+        playing_data.active_data.spawn_particles(&mut particles);
+        assert_eq!(particles.count(), 2 * MAX_BURST);
+
+        // last frame's hits are gone, only the broken log throws splinters
+        let mut log = LevelObject::new(LevelObjectKind::Log { is_vertical: true, length: 20.0 }, Position::new(20.0, 0.0), Vector::new(0.0, 0.0));
+        log.take_damage(f32::INFINITY);
+        playing_data.active_data.objects = vec![log];
+        playing_data.update_collisions();
+        particles.clear();
+        playing_data.active_data.spawn_particles(&mut particles);
+        assert_eq!(particles.count(), SPLINTERS);
+    }
+
+    #[test]
+    fn test_stack_falls_when_bottom_box_breaks() {
+        let mut playing_data = test_playing_data();
+        playing_data.active_data.objects = vec![
+            LevelObject::new(LevelObjectKind::Box, Position::new(0.0, 0.0), Vector::new(0.0, 0.0)),
+            LevelObject::new(LevelObjectKind::Box, Position::new(0.0, 8.0), Vector::new(0.0, 0.0)),
+            LevelObject::new(LevelObjectKind::Box, Position::new(1.0, 16.0), Vector::new(0.0, 0.0)),
+        ];
+        for obj in playing_data.active_data.objects.iter_mut() {
+            obj.sleep();
+        }
+        playing_data.active_data.objects[0].take_damage(f32::INFINITY);
+
+        for _ in 0..60 {
+            playing_data.update_collisions();
+            playing_data.step_physics();
+        }
+        let objects = &playing_data.active_data.objects;
+        assert_eq!(objects.len(), 2);
+        assert!(objects[0].position.y < 1.0);
+        assert!(objects[1].position.y < 9.0);
+    }
+
+    #[test]
+    fn test_hit_stop_freezes_the_level() {
+        let mut playing_data = test_playing_data();
+        playing_data.active_data.objects = vec![
+            LevelObject::new(LevelObjectKind::Player(Avatar::Normal), Position::new(0.0, 10.0), Vector::new(100.0, 0.0)),
+            LevelObject::new(LevelObjectKind::Box, Position::new(7.0, 10.0), Vector::new(0.0, 0.0)),
+        ];
+        playing_data.player_status = PlayerStatus::Ballistic(Vector::new(100.0, 0.0));
+        let before = playing_data.active_data.objects.clone();
+        let mut mode = GameMode::Playing(Box::new(playing_data));
+
+        let mut frame = Frame::new(Position::new(-80.0, -80.0));
+        frame.impact(f32::INFINITY);
+        mode.update(GamePadTracker::new(), frame);
+
+        let GameMode::Playing(playing_data) = &mode else { panic!() };
+        let objects = &playing_data.active_data.objects;
+        assert_eq!(objects.len(), 2);
+        for (obj, old) in objects.iter().zip(before.iter()) {
+            assert_eq!((obj.position, obj.velocity, obj.hit_points), (old.position, old.velocity, old.hit_points));
+        }
+        assert_eq!(mode.heaviest_hit(), None);
+    }
+
+    #[test]
+    fn test_flying_cow_animates() {
+        let mut player = LevelObject::new(LevelObjectKind::Player(Avatar::Normal), Position::new(0.0, 20.0), Vector::new(10.0, 10.0));
+        player.update_animation();
+        assert!(player.animator.current_sprite().is_some());
+
+        player.sleep();
+        player.update_animation();
+        assert!(player.animator.current_sprite().is_none());
+    }
+
+    #[test]
+    fn test_scarecrow_takes_more_hits_than_farmer() {
+        let physics: Physics = Default::default();
+        let hits_to_defeat = |enemy: EnemyAvatar| {
+            let mut target = LevelObject::new(LevelObjectKind::Enemy(enemy), Position::new(7.0, 0.0), Vector::new(0.0, 0.0));
+            let mut hits = 0;
+            while !target.is_destroyed() {
+                let mut player = LevelObject::new(LevelObjectKind::Player(Avatar::Normal), Position::new(0.0, 0.0), Vector::new(30.0, 0.0));
+                target.velocity = Vector::default();
+                player.collide(&mut target, Vector::new(1.0, 0.0), Position::new(7.5, 4.0), physics);
+                hits += 1;
+            }
+            hits
+        };
+
+        assert!(hits_to_defeat(EnemyAvatar::Scarecrow) > hits_to_defeat(EnemyAvatar::Farmer));
+    }
+
+    #[test]
+    fn test_level_lost_when_out_of_cows() {
+        let mut playing_data = test_playing_data();
+        for _ in 0..3 {
+            assert!(matches!(playing_data.level_status(), LevelStatus::InProgress));
+            playing_data.apply_transition(PlayerTransition::Grabbed(Position::new(0.0, 0.0)));
+            playing_data.apply_transition(PlayerTransition::Released(Vector::new(1.0, 1.0)));
+            assert!(matches!(playing_data.level_status(), LevelStatus::InProgress));
+            playing_data.apply_transition(PlayerTransition::Reset);
+            playing_data.update_after_transition(PlayerTransition::Reset);
+        }
+        assert!(matches!(playing_data.level_status(), LevelStatus::Lost));
+
+        playing_data.restart_level();
+        assert!(matches!(playing_data.level_status(), LevelStatus::InProgress));
+    }
+
+    #[test]
+    fn test_each_reset_loads_the_next_cow() {
+        let mut playing_data = test_playing_data();
+        playing_data.levels[0].ammo = VecDeque::from([Avatar::Longhorn, Avatar::Chocolate]);
+        playing_data.restart_level();
+        assert!(matches!(playing_data.active_data.get_player_object().unwrap().kind, LevelObjectKind::Player(Avatar::Longhorn)));
+        assert_eq!(playing_data.active_data.ammo.len(), 1);
+
+        playing_data.apply_transition(PlayerTransition::Grabbed(Position::new(0.0, 0.0)));
+        playing_data.apply_transition(PlayerTransition::Released(Vector::new(1.0, 1.0)));
+        playing_data.apply_transition(PlayerTransition::Reset);
+        playing_data.update_after_transition(PlayerTransition::Reset);
+
+        let players: Vec<_> = playing_data.active_data.objects.iter().filter(|obj| obj.is_player()).collect();
+        assert_eq!(players.len(), 1);
+        assert!(matches!(players[0].kind, LevelObjectKind::Player(Avatar::Chocolate)));
+        assert_eq!(players[0].center(), playing_data.active_data.slingshot.get_position());
+        assert!(playing_data.active_data.ammo.is_empty());
+    }
+
+    #[test]
+    fn test_farmer_patrols_and_flees() {
+        let mut playing_data = test_playing_data();
+        let farmer = &mut playing_data.active_data.objects[0];
+        *farmer = farmer.with_behaviour(Behaviour::Patrol(Patrol::new([30.0, 60.0], 10.0, 40.0, 30.0)));
+
+        for _ in 0..60 {
+            playing_data.update_collisions();
+            playing_data.step_physics();
+        }
+        let farmer = playing_data.active_data.objects[0];
+        assert!(farmer.velocity.x < 0.0 && farmer.center().x < 44.0);
+
+        // patrolling doesn't stop the level from settling
+        assert!(!farmer.is_asleep());
+        assert!(playing_data.active_data.is_settled());
+
+        // a box crashing down to its left sends it running right
+        playing_data.active_data.objects.push(
+            LevelObject::new(LevelObjectKind::Box, Position::new(farmer.center().x - 20.0, 1.0), Vector::new(0.0, -80.0))
+        );
+        for _ in 0..2 {
+            playing_data.update_collisions();
+            playing_data.step_physics();
+        }
+        assert!(playing_data.active_data.objects[0].velocity.x > 10.0);
+    }
+
+    #[test]
+    fn test_patrol_does_not_wake_sleeping_platform() {
+        let mut playing_data = test_playing_data();
+        let mut platform = LevelObject::new(LevelObjectKind::Log { is_vertical: false, length: 40.0 }, Position::new(0.0, 0.0), Vector::new(0.0, 0.0));
+        platform.sleep();
+        let farmer = LevelObject::new(LevelObjectKind::Enemy(EnemyAvatar::Farmer), Position::new(10.0, 3.0), Vector::new(0.0, 0.0))
+            .with_behaviour(Behaviour::Patrol(Patrol::new([5.0, 35.0], 10.0, 40.0, 30.0)));
+        playing_data.active_data.objects = vec![platform, farmer];
+
+        for _ in 0..120 {
+            playing_data.update_collisions();
+            playing_data.step_physics();
+            assert!(playing_data.active_data.objects[0].is_asleep());
+            assert!(playing_data.active_data.is_settled());
+        }
+        let farmer = playing_data.active_data.objects[1];
+        assert!(farmer.position.y > 2.0);
+        assert_ne!(farmer.position.x, 10.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "only farmers can patrol")]
+    fn test_patrolling_scarecrow_is_rejected() {
+        LevelData::parse_levels(r#"[{
+            "slingshot": {"position": {"x": -60.0, "y": 12.0}, "max_stretch": 24.0, "max_speed": 80.0, "power_exponent": 1.5},
+            "ammo": ["Normal"],
+            "objects": [{
+                "kind": {"Enemy": "Scarecrow"},
+                "position": {"x": 40.0, "y": 0.0},
+                "velocity": {"x": 0.0, "y": 0.0},
+                "behaviour": {"Patrol": {"waypoints": [30.0, 50.0], "speed": 8.0, "alert_radius": 40.0, "flee_speed": 24.0}}
+            }]
+        }]"#);
+    }
+
+    #[test]
+    fn test_sprites_match_hitboxes() {
+        for avatar in [Avatar::Normal, Avatar::Longhorn, Avatar::Dairy, Avatar::Chocolate] {
+            let (sprite, hitbox) = (avatar.sprite(), avatar.get_hitbox());
+            assert_eq!((sprite.width as f32, sprite.height as f32), (hitbox.width, hitbox.height));
+        }
+        for e_avatar in [EnemyAvatar::Farmer, EnemyAvatar::Scarecrow] {
+            let (sprite, hitbox) = (e_avatar.sprite(), e_avatar.get_hitbox());
+            assert_eq!((sprite.width as f32, sprite.height as f32), (hitbox.width, hitbox.height));
+        }
+    }
+
+    #[test]
+    fn test_camera_follows_launched_cow() {
+        let mut playing_data = test_playing_data();
+        let mut frame = Frame::new(Position::new(0.0, 0.0));
+
+        // a wide level leaves room to scroll right
+        playing_data.levels[0].objects.push(LevelObject::new(LevelObjectKind::Box, Position::new(200.0, 0.0), Vector::new(0.0, 0.0)));
+        playing_data.restart_level();
+        for _ in 0..200 {
+            playing_data.update_camera(&mut frame, GamePadTracker::new());
+        }
+        let home = frame.get_position();
+        assert!(frame.get_zoom() < 1.0);
+
+        for transition in [PlayerTransition::Grabbed(Position::new(0.0, 0.0)), PlayerTransition::Released(Vector::new(80.0, 20.0))] {
+            playing_data.apply_transition(transition);
+            playing_data.update_after_transition(transition);
+        }
+        for _ in 0..90 {
+            playing_data.update_collisions();
+            playing_data.step_physics();
+            playing_data.update_camera(&mut frame, GamePadTracker::new());
+        }
+        assert!(frame.get_position().x > home.x);
+        assert!(frame.get_zoom() > 0.99);
+
+        playing_data.apply_transition(PlayerTransition::Reset);
+        playing_data.update_after_transition(PlayerTransition::Reset);
+        for _ in 0..200 {
+            playing_data.update_camera(&mut frame, GamePadTracker::new());
+        }
+        assert!((frame.get_position().x - home.x).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_camera_drifts_home_in_small_levels() {
+        let mut playing_data = launched(Avatar::Normal);
+        let home = playing_data.active_data.camera_home();
+        let mut frame = Frame::new(home + Vector::new(60.0, 30.0));
+
+        playing_data.apply_transition(PlayerTransition::Reset);
+        playing_data.update_after_transition(PlayerTransition::Reset);
+        for _ in 0..200 {
+            playing_data.update_camera(&mut frame, GamePadTracker::new());
+        }
+        assert!(Vector::between(frame.get_position(), home).magnitude() < 0.01);
+        assert_eq!(frame.get_zoom(), 1.0);
+    }
+
+    #[test]
+    fn test_panning_ends_when_aiming() {
+        let mut playing_data = test_playing_data();
+        let mut frame = Frame::new(Position::new(-80.0, -80.0));
+        let mut gpt = GamePadTracker::new();
+
+        gpt.update(BUTTON_LEFT, 0, 0, 0);
+        playing_data.update_camera(&mut frame, gpt);
+        let panned = frame.get_position();
+        assert!(frame.is_panning());
+        assert!(panned.x < -80.0);
+        playing_data.update_camera(&mut frame, gpt);
+        assert!(frame.get_position().x < panned.x);
+
+        playing_data.apply_transition(PlayerTransition::Grabbed(Position::new(0.0, 0.0)));
+        playing_data.update_camera(&mut frame, gpt);
+        assert!(!frame.is_panning());
+        playing_data.update_camera(&mut frame, gpt);
+        assert!(!frame.is_panning());
+    }
+
+    #[test]
+    fn test_final_level() {
+        let mut playing_data = test_playing_data();
+        assert!(!playing_data.is_final_level());
+        playing_data.next_level();
+        assert!(playing_data.is_final_level());
     }
 
     #[test]
@@ -548,6 +2121,7 @@ mod tests {
         let col = Collision {
             obj1: (2.0, Vector::new(3.0, 0.0)),
             obj2: (1.0, Vector::new(0.0, 0.0)),
+            normal: Vector::new(1.0, 0.0),
             kind: CollisionKind::PerfectlyInelastic,
         };
 
@@ -567,6 +2141,7 @@ mod tests {
         let col = Collision {
             obj1: (m, v1),
             obj2: (m, v2),
+            normal: Vector::new(1.0, 0.0),
             kind: CollisionKind::Elastic,
         };
 
@@ -585,6 +2160,7 @@ mod tests {
         let col = Collision {
             obj1: (m, v1),
             obj2: (m, v2),
+            normal: Vector::new(-1.0, 0.0),
             kind: CollisionKind::Elastic,
         };
 
@@ -604,12 +2180,472 @@ mod tests {
         let col = Collision {
             obj1: (m, v1),
             obj2: (m, v2),
+            normal: Vector::new(-1.0, 0.0),
             kind: CollisionKind::Damping(damping_factor),
         };
 
         let (v1_f, v2_f) = col.simulate();
 
-        assert_eq!(v1_f, v2 * (1. - damping_factor));
-        assert_eq!(v2_f, v1 * (1. - damping_factor));
+        // momentum is conserved and the separation speed is damped
+        assert!((m * (v1_f + v2_f) - m * (v1 + v2)).magnitude() < 1e-4);
+        assert!(((v2_f - v1_f).x.abs() - (v2 - v1).x.abs() * (1. - damping_factor)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_glancing_collision_keeps_tangential_velocity() {
+        let v1 = Vector::new(3.0, -2.0);
+        let v2 = Vector::new(0.0, 1.0);
+        let m = 10.0;
+
+        let col = Collision {
+            obj1: (m, v1),
+            obj2: (m, v2),
+            normal: Vector::new(1.0, 0.0),
+            kind: CollisionKind::Damping(0.05),
+        };
+
+        let (v1_f, v2_f) = col.simulate();
+
+        assert_eq!(v1_f.y, v1.y);
+        assert_eq!(v2_f.y, v2.y);
+        assert!(v1_f.x < v2_f.x);
+    }
+
+    #[test]
+    fn test_sliding_box_comes_to_rest() {
+        let physics: Physics = Default::default();
+        let mut obj = LevelObject::new(LevelObjectKind::Box, Position::new(0.0, 0.0), Vector::new(20.0, 0.0));
+
+        for _ in 0..60 {
+            obj.step_physics(physics);
+        }
+
+        assert_eq!(obj.velocity.x, 0.0);
+        assert!(obj.position.x > 0.0);
+    }
+
+    #[test]
+    fn test_airborne_box_keeps_sliding() {
+        let physics: Physics = Default::default();
+        let mut obj = LevelObject::new(LevelObjectKind::Box, Position::new(0.0, 30.0), Vector::new(20.0, 0.0));
+        obj.step_physics(physics);
+
+        assert_eq!(obj.velocity.x, 20.0);
+    }
+
+    #[test]
+    fn test_contact_friction() {
+        let col = Collision {
+            obj1: (10.0, Vector::new(5.0, -1.0)),
+            obj2: (10.0, Vector::new(0.0, 0.0)),
+            normal: Vector::new(0.0, -1.0),
+            kind: CollisionKind::PerfectlyInelastic,
+        };
+
+        // kinetic friction slows the sliding without reversing it
+        let friction = col.friction_impulse(0.6, 0.4);
+        assert_eq!(friction.y, 0.0);
+        assert!(friction.x > 0.0);
+        assert!(friction.x < 25.0);
+
+        // enough static friction makes the objects stick together
+        let friction = col.friction_impulse(10.0, 0.4);
+        assert_eq!(friction.x, 25.0);
+    }
+
+    #[test]
+    fn test_separating_objects_do_not_collide() {
+        let v1 = Vector::new(-3.0, 0.0);
+        let v2 = Vector::new(4.0, 0.0);
+
+        let col = Collision {
+            obj1: (10.0, v1),
+            obj2: (10.0, v2),
+            normal: Vector::new(1.0, 0.0),
+            kind: CollisionKind::Elastic,
+        };
+
+        assert_eq!(col.simulate(), (v1, v2));
+    }
+
+    #[test]
+    fn test_overlapping_boxes_separate() {
+        let mut playing_data = test_playing_data();
+        playing_data.active_data.objects = vec![
+            LevelObject::new(LevelObjectKind::Box, Position::new(0.0, 20.0), Vector::new(0.0, 0.0)),
+            LevelObject::new(LevelObjectKind::Box, Position::new(6.0, 20.0), Vector::new(0.0, 0.0)),
+            LevelObject::new(LevelObjectKind::Box, Position::new(30.0, 0.0), Vector::new(0.0, 0.0)),
+            LevelObject::new(LevelObjectKind::Box, Position::new(30.0, 7.0), Vector::new(0.0, 0.0)),
+        ];
+        playing_data.update_collisions();
+
+        let objects = &playing_data.active_data.objects;
+        assert_eq!(objects[0].position, Position::new(-1.0, 20.0));
+        assert_eq!(objects[1].position, Position::new(7.0, 20.0));
+        assert!(!objects[0].collides_with(&objects[1]));
+
+        // the bottom box stays on the ground and the top one rests on it
+        assert_eq!(objects[2].position, Position::new(30.0, 0.0));
+        assert_eq!(objects[3].position, Position::new(30.0, 8.0));
+    }
+
+    #[test]
+    fn test_fast_player_does_not_tunnel_through_log() {
+        let mut playing_data = test_playing_data();
+        playing_data.active_data.objects = vec![
+            LevelObject::new(LevelObjectKind::Player(Avatar::Normal), Position::new(0.0, 10.0), Vector::new(1500.0, 0.0)),
+            LevelObject::new(LevelObjectKind::Log { is_vertical: true, length: 30.0 }, Position::new(12.0, 0.0), Vector::new(0.0, 0.0)),
+            LevelObject::new(LevelObjectKind::Enemy(EnemyAvatar::Farmer), Position::new(60.0, 0.0), Vector::new(0.0, 0.0)),
+        ];
+        // unbreakable so only the tunnelling is tested
+        playing_data.active_data.objects[1].hit_points = f32::INFINITY;
+        assert!(playing_data.active_data.objects[0].is_fast(playing_data.active_data.physics));
+
+        playing_data.update_collisions();
+        playing_data.step_physics();
+
+        let objects = &playing_data.active_data.objects;
+        assert!(objects[0].position.x + 8.0 <= objects[1].position.x);
+        assert!(objects[0].velocity.x < 1500.0);
+        assert!(objects[1].velocity.x > 0.0);
+    }
+
+    #[test]
+    fn test_fast_hit_lands_where_the_objects_touch() {
+        let mut playing_data = test_playing_data();
+        playing_data.active_data.objects = vec![
+            LevelObject::new(LevelObjectKind::Player(Avatar::Normal), Position::new(0.0, 20.0), Vector::new(1500.0, 0.0)),
+            LevelObject::new(LevelObjectKind::Log { is_vertical: true, length: 30.0 }, Position::new(12.0, 0.0), Vector::new(0.0, 0.0)),
+            LevelObject::new(LevelObjectKind::Enemy(EnemyAvatar::Farmer), Position::new(60.0, 0.0), Vector::new(0.0, 0.0)),
+        ];
+        playing_data.active_data.objects[1].hit_points = f32::INFINITY;
+
+        playing_data.update_collisions();
+        let hit = playing_data.active_data.hits[0];
+        assert!((hit.point.x - 12.0).abs() < 1e-3);
+        assert!(hit.point.y > 20.0 && hit.point.y < 28.0);
+        // hit near the top, so the log starts tipping over
+        assert!(playing_data.active_data.objects[1].angular_velocity < 0.0);
+    }
+
+    #[test]
+    fn test_swept_objects_only_finish_the_step() {
+        let physics: Physics = Default::default();
+        let mut swept = LevelObject::new(LevelObjectKind::Box, Position::new(0.0, 50.0), Vector::new(600.0, 0.0));
+        let mut stepped = swept;
+
+        swept.advance(0.4, physics);
+        swept.step_physics(physics);
+        stepped.step_physics(physics);
+        assert!((swept.position.x - stepped.position.x).abs() < 1e-4);
+        assert_eq!(swept.velocity, stepped.velocity);
+
+        // the next step is a whole one again
+        swept.step_physics(physics);
+        stepped.step_physics(physics);
+        assert!((swept.position.x - stepped.position.x).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_sweep_finds_first_contact() {
+        let rect = Rect::new(8.0, 8.0);
+        let log = Rect::new(3.0, 20.0);
+
+        let impact = rect.sweep(Position::new(0.0, 5.0), Vector::new(20.0, 0.0), log, Position::new(12.0, 0.0)).unwrap();
+        assert_eq!(impact.time, 0.2);
+        assert_eq!(impact.normal, Vector::new(1.0, 0.0));
+
+        // too slow to reach it this step
+        assert!(rect.sweep(Position::new(0.0, 5.0), Vector::new(2.0, 0.0), log, Position::new(12.0, 0.0)).is_none());
+        // passes above it
+        assert!(rect.sweep(Position::new(0.0, 25.0), Vector::new(20.0, 0.0), log, Position::new(12.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn test_log_hit_near_the_top_topples() {
+        let mut playing_data = test_playing_data();
+        playing_data.active_data.objects = vec![
+            LevelObject::new(LevelObjectKind::Log { is_vertical: true, length: 20.0 }, Position::new(30.0, 0.0), Vector::new(0.0, 0.0)),
+            LevelObject::new(LevelObjectKind::Player(Avatar::Normal), Position::new(20.0, 14.0), Vector::new(60.0, 0.0)),
+            LevelObject::new(LevelObjectKind::Enemy(EnemyAvatar::Farmer), Position::new(-60.0, 0.0), Vector::new(0.0, 0.0)),
+        ];
+
+        for _ in 0..300 {
+            playing_data.update_collisions();
+            playing_data.step_physics();
+        }
+
+        // knocked clockwise and now lying on the ground
+        let log = &playing_data.active_data.objects[0];
+        assert!(log.angle < 0.0);
+        assert!(log.angle.cos().abs() < 0.1);
+        assert!(log.is_grounded());
+
+        // and it comes to rest there instead of rocking from one end to the other
+        for _ in 0..600 {
+            playing_data.update_collisions();
+            playing_data.step_physics();
+        }
+        assert!(playing_data.active_data.objects[0].is_asleep());
+    }
+
+    #[test]
+    fn test_log_landing_almost_flat_stops_rocking() {
+        let physics: Physics = Default::default();
+        let flat = std::f32::consts::FRAC_PI_2;
+        for (angle, angular_velocity) in [(flat + 0.01, 0.0), (flat - 0.01, 0.0), (flat, -1.0)] {
+            let mut log = LevelObject::new(LevelObjectKind::Log { is_vertical: true, length: 20.0 }, Position::new(30.0, 5.0), Vector::new(0.0, 0.0));
+            log.angle = angle;
+            log.angular_velocity = angular_velocity;
+
+            let mut still_frames = 0;
+            for _ in 0..600 {
+                log.step_physics(physics);
+                still_frames = log.update_still_frames();
+            }
+            assert!(still_frames >= SLEEP_FRAMES);
+            assert!(log.angle.cos().abs() < 0.1);
+        }
+    }
+
+    #[test]
+    fn test_standing_log_stays_up() {
+        let physics: Physics = Default::default();
+        let mut log = LevelObject::new(LevelObjectKind::Log { is_vertical: true, length: 20.0 }, Position::new(30.0, 0.0), Vector::new(0.0, 0.0));
+
+        for _ in 0..300 {
+            log.step_physics(physics);
+        }
+
+        assert!(log.angle.abs() < 1e-3);
+        assert!(log.position.y.abs() < 0.1);
+    }
+
+    #[test]
+    fn test_oriented_contact() {
+        let log = Rect::new(3.0, 20.0);
+        let rect = Rect::new(8.0, 8.0);
+
+        // a log tipped 45 degrees reaches over a box it wouldn't touch standing up
+        let position = Position::new(0.0, 0.0);
+        let angle = -std::f32::consts::FRAC_PI_4;
+        assert!(log.oriented_contact(position, 0.0, rect, Position::new(5.0, 10.0), 0.0).is_none());
+
+        let contact = log.oriented_contact(position, angle, rect, Position::new(5.0, 10.0), 0.0).unwrap();
+        assert!(contact.depth > 0.0);
+        assert!(contact.normal.x > 0.0);
+    }
+
+    #[test]
+    fn test_resting_objects_fall_asleep() {
+        let mut playing_data = test_playing_data();
+        playing_data.active_data.objects = vec![
+            LevelObject::new(LevelObjectKind::Box, Position::new(0.0, 0.0), Vector::new(0.0, 0.0)),
+            LevelObject::new(LevelObjectKind::Box, Position::new(1.0, 10.0), Vector::new(0.0, 0.0)),
+            LevelObject::new(LevelObjectKind::Box, Position::new(40.0, 0.0), Vector::new(30.0, 0.0)),
+        ];
+        assert!(!playing_data.active_data.is_settled());
+
+        for _ in 0..(SLEEP_FRAMES * 4) {
+            playing_data.update_collisions();
+            playing_data.step_physics();
+        }
+        assert!(playing_data.active_data.is_settled());
+
+        // a hit wakes the box up again
+        playing_data.active_data.objects.push(
+            LevelObject::new(LevelObjectKind::Box, Position::new(1.0, 12.0), Vector::new(0.0, -20.0))
+        );
+        playing_data.update_collisions();
+        assert!(!playing_data.active_data.objects[1].is_asleep());
+        assert!(!playing_data.active_data.is_settled());
+    }
+
+    #[test]
+    fn test_player_resets_once_settled() {
+        let mut playing_data = test_playing_data();
+        playing_data.apply_transition(PlayerTransition::Grabbed(Position::new(0.0, 0.0)));
+        playing_data.apply_transition(PlayerTransition::Released(Vector::new(5.0, 0.0)));
+        playing_data.update_after_transition(PlayerTransition::Released(Vector::new(5.0, 0.0)));
+
+        let gpt = GamePadTracker::new();
+        let frame = Frame::new(Position::new(-80.0, -80.0));
+        assert!(playing_data.get_player_transition(gpt, frame).is_none());
+
+        // the cow is launched from the raised slingshot, so it drops and bounces
+        // for about five times `SLEEP_FRAMES` before it can start falling asleep
+        for _ in 0..(SLEEP_FRAMES * 10) {
+            playing_data.update_collisions();
+            playing_data.step_physics();
+        }
+        assert!(matches!(playing_data.get_player_transition(gpt, frame), Some(PlayerTransition::Reset)));
+    }
+
+    #[test]
+    fn test_level_settles_after_log_topples() {
+        let mut playing_data = test_playing_data();
+        playing_data.active_data.objects = vec![
+            LevelObject::new(LevelObjectKind::Log { is_vertical: true, length: 20.0 }, Position::new(30.0, 0.0), Vector::new(0.0, 0.0)),
+            LevelObject::new(LevelObjectKind::Player(Avatar::Normal), Position::new(20.0, 14.0), Vector::new(60.0, 0.0)),
+            LevelObject::new(LevelObjectKind::Enemy(EnemyAvatar::Farmer), Position::new(-60.0, 0.0), Vector::new(0.0, 0.0)),
+        ];
+
+        // well before the next cow would be loaded anyway
+        for _ in 0..MAX_SETTLE_FRAMES / 2 {
+            playing_data.update_collisions();
+            playing_data.step_physics();
+        }
+        assert!(playing_data.active_data.objects[0].angle.cos().abs() < 0.1);
+        assert!(playing_data.active_data.is_settled());
+    }
+
+    #[test]
+    fn test_player_resets_if_level_never_settles() {
+        let mut playing_data = test_playing_data();
+        for transition in [PlayerTransition::Grabbed(Position::new(0.0, 0.0)), PlayerTransition::Released(Vector::new(5.0, 0.0))] {
+            playing_data.apply_transition(transition);
+            playing_data.update_after_transition(transition);
+        }
+        let gpt = GamePadTracker::new();
+        let frame = Frame::new(Position::new(-80.0, -80.0));
+
+        // the cow keeps sliding, as if something kept jittering
+        for _ in 0..MAX_SETTLE_FRAMES {
+            assert!(playing_data.get_player_transition(gpt, frame).is_none());
+            playing_data.active_data.get_mut_player_object().unwrap().set_velocity(Vector::new(5.0, 0.0));
+            playing_data.update_collisions();
+            playing_data.step_physics();
+        }
+        assert!(!playing_data.active_data.is_settled());
+        assert!(matches!(playing_data.get_player_transition(gpt, frame), Some(PlayerTransition::Reset)));
+    }
+
+    fn launched(avatar: Avatar) -> PlayingData {
+        let mut playing_data = test_playing_data();
+        playing_data.levels[0].ammo[0] = avatar;
+        playing_data.restart_level();
+        for transition in [PlayerTransition::Grabbed(Position::new(0.0, 0.0)), PlayerTransition::Released(Vector::new(40.0, 30.0))] {
+            playing_data.apply_transition(transition);
+            playing_data.update_after_transition(transition);
+        }
+        playing_data
+    }
+
+    fn use_ability(playing_data: &mut PlayingData) {
+        playing_data.apply_transition(PlayerTransition::Ability);
+        playing_data.update_after_transition(PlayerTransition::Ability);
+    }
+
+    #[test]
+    fn test_longhorn_dashes_forward() {
+        let mut playing_data = launched(Avatar::Longhorn);
+        use_ability(&mut playing_data);
+
+        let velocity = playing_data.active_data.get_player_object().unwrap().velocity;
+        assert!((velocity.magnitude() - (50.0 + LONGHORN_DASH_SPEED)).abs() < 1e-3);
+        assert!((velocity.normalize().dot(Vector::new(0.8, 0.6)) - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_dairy_splits_in_three() {
+        let mut playing_data = launched(Avatar::Dairy);
+        use_ability(&mut playing_data);
+
+        let players: Vec<_> = playing_data.active_data.objects.iter().filter(|obj| obj.is_player()).copied().collect();
+        assert_eq!(players.len(), 3);
+        for i in 0..players.len() {
+            for j in (i + 1)..players.len() {
+                assert!(!players[i].collides_with(&players[j]));
+            }
+        }
+
+        // the copies are gone once the cow is back in the slingshot
+        playing_data.apply_transition(PlayerTransition::Reset);
+        playing_data.update_after_transition(PlayerTransition::Reset);
+        assert_eq!(playing_data.active_data.objects.iter().filter(|obj| obj.is_player()).count(), 1);
+    }
+
+    #[test]
+    fn test_still_dairy_splits_sideways() {
+        let player = LevelObject::new(LevelObjectKind::Player(Avatar::Dairy), Position::new(0.0, 0.0), Vector::new(0.0, 0.0));
+        let copies = player.split(DAIRY_SPLIT_ANGLE);
+        for copy in copies.iter() {
+            assert!(copy.position.x.is_finite() && copy.position.y.is_finite());
+            assert_eq!(copy.position.y, 0.0);
+            assert!(!copy.collides_with(&player));
+        }
+        assert!(!copies[0].collides_with(&copies[1]));
+    }
+
+    #[test]
+    fn test_chocolate_slams_down() {
+        let mut playing_data = launched(Avatar::Chocolate);
+        use_ability(&mut playing_data);
+
+        let player = playing_data.active_data.get_player_object().unwrap();
+        assert_eq!(player.velocity, Vector::new(0.0, -CHOCOLATE_SLAM_SPEED));
+        assert_eq!(player.get_mass(), Avatar::Chocolate.get_mass() + CHOCOLATE_SLAM_MASS);
+
+        // and is back to normal for the next shot
+        playing_data.restart_level();
+        assert_eq!(playing_data.active_data.get_player_object().unwrap().get_mass(), Avatar::Chocolate.get_mass());
+    }
+
+    #[test]
+    fn test_ability_works_once_per_shot() {
+        let mut playing_data = launched(Avatar::Longhorn);
+        let mut gpt = GamePadTracker::new();
+        gpt.update(BUTTON_1, 0, 0, 0);
+        let frame = Frame::new(Position::new(-80.0, -80.0));
+        assert!(matches!(playing_data.get_player_transition(gpt, frame), Some(PlayerTransition::Ability)));
+
+        use_ability(&mut playing_data);
+        assert!(playing_data.get_player_transition(gpt, frame).is_none());
+    }
+
+    #[test]
+    fn test_trajectory_matches_flight() {
+        let level = test_playing_data().active_data;
+        let mut player = *level.get_player_object().unwrap();
+        player.set_velocity(level.slingshot.release_velocity(Position::new(-80.0, -8.0)));
+
+        let mut predicted = Vec::new();
+        level.predict_trajectory(player, |position| predicted.push(position));
+        assert!(!predicted.is_empty());
+
+        for expected in predicted {
+            player.step_physics(level.physics);
+            assert_eq!(player.get_position(), expected);
+        }
+        // the prediction ended where the cow comes back down
+        let displacement = player.next_displacement(level.physics);
+        assert!(player.get_position().y + displacement.y <= 0.0);
+    }
+
+    #[test]
+    fn test_trajectory_stops_at_objects() {
+        let mut level = test_playing_data().active_data;
+        level.objects.push(LevelObject::new(LevelObjectKind::Log { is_vertical: true, length: 100.0 }, Position::new(-30.0, 0.0), Vector::new(0.0, 0.0)));
+        let mut player = *level.get_player_object().unwrap();
+        player.set_velocity(Vector::new(40.0, 40.0));
+
+        let mut last = None;
+        level.predict_trajectory(player, |position| last = Some(position));
+        assert!(last.unwrap().x + 8.0 <= -30.0);
+    }
+
+    #[test]
+    fn test_contact_normal_uses_least_penetration() {
+        let rect = Rect::new(8.0, 8.0);
+
+        let side = rect.contact(Position::new(0.0, 0.0), rect, Position::new(6.0, 1.0)).unwrap();
+        assert_eq!(side.normal, Vector::new(1.0, 0.0));
+        assert_eq!(side.depth, 2.0);
+
+        let stacked = rect.contact(Position::new(0.0, 7.0), rect, Position::new(1.0, 0.0)).unwrap();
+        assert_eq!(stacked.normal, Vector::new(0.0, -1.0));
+        assert_eq!(stacked.depth, 1.0);
+
+        assert!(rect.contact(Position::new(0.0, 0.0), rect, Position::new(9.0, 0.0)).is_none());
     }
 }
\ No newline at end of file
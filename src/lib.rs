@@ -21,6 +21,26 @@ use game_pad_tracker::GamePadTracker;
 mod level_object;
 use level_object::{GameState, GameMode};
 
+mod broadphase;
+
+mod sleep;
+
+mod slingshot;
+
+mod enemy;
+
+mod sprites;
+
+mod animation;
+
+mod particles;
+use particles::Particles;
+
+mod hud;
+
+mod palettes;
+use palettes::PaletteFade;
+
 mod constants;
 
 #[no_mangle]
@@ -29,19 +49,12 @@ unsafe fn start() {
     *DRAW_COLORS = 0x23;
 }
 
-mod palettes {
-    const GRAY: u32 = 0xFFafb2a3;
-    const DARK_GREEN: u32 = 0xFF2a4f1c;
-    const ORANGE: u32 = 0xFFf49271;
-    const GREEN: u32 = 0xFF587d3f;
-    pub const MOSSY: [u32; 4] = [GREEN, DARK_GREEN, ORANGE, GRAY];
-    pub const ICY: [u32; 4] = [0x03045e, 0x0096c7, 0x90e0ef, GRAY];
-}
-
 static mut GAME: GameState = GameState {
     gpt: GamePadTracker::new(),
     frame: Frame::new(Position::new(-80.0, -80.0)),
     mode: GameMode::TitleScreen,
+    particles: Particles::new(),
+    palette: PaletteFade::new(palettes::MOSSY),
 };
 
 
@@ -87,27 +100,9 @@ unsafe fn update() {
     //     obj.update_projectile_from_inputs(INPUTS, FRAME);
     //     obj.draw(FRAME);
     // }
-    
-    // click and drag middle mouse button for this instead
-    // if INPUTS.pressed(BUTTON_RIGHT) {
-    //     FRAME.mv(Vector::new(1.0, 0.0))
-    // }
-    // if INPUTS.pressed(BUTTON_LEFT) {
-    //     FRAME.mv(Vector::new(-1.0, 0.0))
-    // }
-    // if INPUTS.pressed(BUTTON_DOWN) {
-    //     FRAME.mv(Vector::new(0.0, -1.0))
-    // }
-    // if INPUTS.pressed(BUTTON_UP) {
-    //     FRAME.mv(Vector::new(0.0, 1.0))
-    // }
-
-    // button to lock/unlock screen from following ball
 
     // collision physics
 
-    // follow ball like mario's camera (after a certain line the frame moves)
-
     // FRAME.draw(BALL.object, BALL.position);
     // FRAME.draw(BALL_2.object, BALL_2.position);
 }
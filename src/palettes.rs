@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+use crate::constants::PALETTE_FADE_FRAMES;
+
+
+// Every palette gives its four colors the same roles, so anything drawn with the
+// `DRAW_COLORS` below (and sprites, see `Sprite::COLORS`) reads the same in every theme:
+// 1 is the background, only ever used to clear the screen, 2 is dark (outlines, enemies),
+// 3 the accent (wood) and 4 light (cows, text). 2-4 all have to stand out against 1.
+pub const DARK: u16 = 0x2;
+pub const ACCENT: u16 = 0x3;
+pub const LIGHT: u16 = 0x4;
+
+const GRAY: u32 = 0xFFafb2a3;
+const DARK_GREEN: u32 = 0xFF2a4f1c;
+const ORANGE: u32 = 0xFFf49271;
+const GREEN: u32 = 0xFF587d3f;
+pub const MOSSY: [u32; 4] = [GREEN, DARK_GREEN, ORANGE, GRAY];
+pub const ICY: [u32; 4] = [0xFF90E0EF, 0xFF03045E, 0xFF0096C7, 0xFFF8F9FA];
+pub const SUNSET: [u32; 4] = [0xFFF4A261, 0xFF3D1F4A, 0xFFBC4B51, 0xFFFBEECB];
+pub const NIGHT: [u32; 4] = [0xFF0B1026, 0xFF5C6B8A, 0xFFE0A458, 0xFFF0EFE0];
+
+/// Palette a level is drawn with, set per level in the level file.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Theme {
+    #[default]
+    Mossy,
+    Icy,
+    Sunset,
+    Night,
+}
+
+impl Theme {
+    pub const fn colors(&self) -> [u32; 4] {
+        match self {
+            Self::Mossy => MOSSY,
+            Self::Icy => ICY,
+            Self::Sunset => SUNSET,
+            Self::Night => NIGHT,
+        }
+    }
+}
+
+/// Mixes two 0xAARRGGBB colors channel by channel, `t` going from 0 (all `from`) to 1 (all `to`).
+fn blend(from: u32, to: u32, t: f32) -> u32 {
+    (0..4).fold(0, |color, i| {
+        let shift = 8 * i;
+        let (a, b) = ((from >> shift & 0xFF) as f32, (to >> shift & 0xFF) as f32);
+        color | ((a + (b - a) * t).round() as u32) << shift
+    })
+}
+
+/// Cross-fades between palettes instead of switching at once.
+#[derive(Copy, Clone, Debug)]
+pub struct PaletteFade {
+    from: [u32; 4],
+    to: [u32; 4],
+    frames: u8,
+}
+
+impl PaletteFade {
+    pub const fn new(colors: [u32; 4]) -> Self {
+        Self { from: colors, to: colors, frames: PALETTE_FADE_FRAMES }
+    }
+
+    /// Starts fading from the colors shown now to `colors`, unless already headed there.
+    pub fn fade_to(&mut self, colors: [u32; 4]) {
+        if colors != self.to {
+            self.from = self.current();
+            self.to = colors;
+            self.frames = 0;
+        }
+    }
+
+    pub fn step(&mut self) {
+        self.frames = (self.frames + 1).min(PALETTE_FADE_FRAMES);
+    }
+
+    pub fn current(&self) -> [u32; 4] {
+        let t = self.frames as f32 / PALETTE_FADE_FRAMES as f32;
+        [0, 1, 2, 3].map(|i| blend(self.from[i], self.to[i], t))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blend() {
+        assert_eq!(blend(0xFF000000, 0xFF204060, 0.0), 0xFF000000);
+        assert_eq!(blend(0xFF000000, 0xFF204060, 0.5), 0xFF102030);
+        assert_eq!(blend(0xFF000000, 0xFF204060, 1.0), 0xFF204060);
+    }
+
+    #[test]
+    fn test_fade_between_themes() {
+        let mut fade = PaletteFade::new(MOSSY);
+        assert_eq!(fade.current(), MOSSY);
+
+        fade.fade_to(NIGHT);
+        assert_eq!(fade.current(), MOSSY);
+        fade.step();
+        let halfway = fade.current();
+        assert!(halfway != MOSSY && halfway != NIGHT);
+
+        // heading back starts from the colors on screen, not from the old palette
+        fade.fade_to(MOSSY);
+        assert_eq!(fade.current(), halfway);
+        for _ in 0..PALETTE_FADE_FRAMES {
+            fade.step();
+        }
+        assert_eq!(fade.current(), MOSSY);
+    }
+
+    #[test]
+    fn test_every_palette_is_opaque() {
+        for theme in [Theme::Mossy, Theme::Icy, Theme::Sunset, Theme::Night] {
+            assert!(theme.colors().iter().all(|color| color >> 24 == 0xFF));
+        }
+    }
+}
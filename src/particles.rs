@@ -0,0 +1,163 @@
+use crate::constants::{TIME_STEP, MAX_PARTICLES};
+use crate::frame::Frame;
+use crate::position::Position;
+use crate::vector::Vector;
+use crate::wasm4::*;
+use crate::palettes;
+
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParticleKind {
+    Dust,
+    Splinter,
+    Milk,
+}
+
+impl ParticleKind {
+    const fn draw_colors(&self) -> u16 {
+        match self {
+            Self::Dust => palettes::DARK,
+            Self::Splinter => palettes::ACCENT,
+            Self::Milk => palettes::LIGHT,
+        }
+    }
+
+    /// Dust barely falls, everything else drops like the objects do.
+    const fn gravity_scale(&self) -> f32 {
+        match self {
+            Self::Dust => 0.1,
+            Self::Splinter | Self::Milk => 1.0,
+        }
+    }
+
+    const fn lifetime(&self) -> u8 {
+        match self {
+            Self::Dust => 20,
+            Self::Splinter => 40,
+            Self::Milk => 30,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Particle {
+    kind: ParticleKind,
+    position: Position,
+    velocity: Vector,
+    /// 0 once the particle is gone
+    frames_left: u8,
+}
+
+impl Particle {
+    const DEAD: Self = Self {
+        kind: ParticleKind::Dust,
+        position: Position::new(0.0, 0.0),
+        velocity: Vector::new(0.0, 0.0),
+        frames_left: 0,
+    };
+}
+
+/// A fixed pool of particles so effects never touch the heap. When it's full
+/// the oldest particle makes room for the new one.
+#[derive(Clone, Debug)]
+pub struct Particles {
+    pool: [Particle; MAX_PARTICLES],
+    next: usize,
+    seed: u32,
+}
+
+impl Particles {
+    pub const fn new() -> Self {
+        Self {
+            pool: [Particle::DEAD; MAX_PARTICLES],
+            next: 0,
+            seed: 12345,
+        }
+    }
+
+    /// `count` particles flying out of `point` at up to `speed`, mostly along `direction`
+    /// (a unit vector, or zero to spray everywhere).
+    pub fn burst(&mut self, kind: ParticleKind, point: Position, count: usize, speed: f32, direction: Vector) {
+        for _ in 0..count {
+            let spray = Vector::noise(&mut self.seed);
+            let boost = Vector::noise(&mut self.seed).x.abs();
+            let velocity = (direction + spray * 0.75) * (speed * (0.5 + 0.5 * boost));
+            self.pool[self.next] = Particle { kind, position: point, velocity, frames_left: kind.lifetime() };
+            self.next = (self.next + 1) % MAX_PARTICLES;
+        }
+    }
+
+    pub fn step(&mut self, gravity: Vector) {
+        for particle in self.pool.iter_mut().filter(|particle| particle.frames_left > 0) {
+            particle.velocity = particle.velocity + gravity * (particle.kind.gravity_scale() * TIME_STEP);
+            particle.position = particle.position + particle.velocity * TIME_STEP;
+            // nothing bounces, particles just stop at the ground
+            if particle.position.y < 0.0 {
+                particle.position.y = 0.0;
+                particle.velocity = Vector::default();
+            }
+            particle.frames_left -= 1;
+        }
+    }
+
+    pub fn draw(&self, frame: Frame) {
+        for particle in self.pool.iter().filter(|particle| particle.frames_left > 0) {
+            let (x, y) = frame.from_units_to_px(particle.position);
+            unsafe { *DRAW_COLORS = particle.kind.draw_colors(); }
+            rect(x, y, 1, 1);
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.pool.iter().filter(|particle| particle.frames_left > 0).count()
+    }
+
+    pub fn clear(&mut self) {
+        self.pool = [Particle::DEAD; MAX_PARTICLES];
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::GRAVITY;
+
+    #[test]
+    fn test_particles_fade_out() {
+        let mut particles = Particles::new();
+        particles.burst(ParticleKind::Dust, Position::new(0.0, 0.0), 5, 10.0, Vector::new(0.0, 1.0));
+        particles.burst(ParticleKind::Splinter, Position::new(0.0, 10.0), 5, 30.0, Vector::default());
+        assert_eq!(particles.count(), 10);
+
+        for _ in 0..ParticleKind::Dust.lifetime() {
+            particles.step(GRAVITY);
+        }
+        assert_eq!(particles.count(), 5);
+        for _ in ParticleKind::Dust.lifetime()..ParticleKind::Splinter.lifetime() {
+            particles.step(GRAVITY);
+        }
+        assert_eq!(particles.count(), 0);
+    }
+
+    #[test]
+    fn test_full_pool_replaces_oldest() {
+        let mut particles = Particles::new();
+        particles.burst(ParticleKind::Dust, Position::new(0.0, 0.0), MAX_PARTICLES, 10.0, Vector::default());
+        particles.burst(ParticleKind::Milk, Position::new(0.0, 0.0), 3, 10.0, Vector::default());
+
+        assert_eq!(particles.count(), MAX_PARTICLES);
+        assert!(particles.pool[..3].iter().all(|particle| particle.kind == ParticleKind::Milk));
+        assert!(particles.pool[3..].iter().all(|particle| particle.kind == ParticleKind::Dust));
+    }
+
+    #[test]
+    fn test_particles_stop_at_the_ground() {
+        let mut particles = Particles::new();
+        particles.burst(ParticleKind::Milk, Position::new(0.0, 1.0), 8, 20.0, Vector::new(0.0, -1.0));
+        for _ in 0..ParticleKind::Milk.lifetime() - 1 {
+            particles.step(GRAVITY);
+        }
+        assert!(particles.pool.iter().all(|particle| particle.position.y >= 0.0));
+    }
+}
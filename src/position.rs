@@ -1,5 +1,6 @@
 use std::ops::Add;
 use serde::{Serialize, Deserialize};
+use crate::vector::Vector;
 
 
 #[derive(Copy, Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
@@ -35,6 +36,16 @@ impl Add for Position {
     }
 }
 
+impl Add<Vector> for Position {
+    type Output = Self;
+    fn add(self, rhs: Vector) -> Self::Output {
+        Self::new(
+            self.x + rhs.x,
+            self.y + rhs.y
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,75 @@
+use crate::constants::SLEEP_FRAMES;
+use crate::level_object::LevelObject;
+
+
+/// Groups of touching objects (union-find over this frame's contacts). An island
+/// only falls asleep once every object in it has been still for `SLEEP_FRAMES`.
+#[derive(Clone, Debug, Default)]
+pub struct Islands {
+    parent: Vec<usize>,
+    still_frames: Vec<u16>,
+}
+
+impl Islands {
+    pub const fn new() -> Self {
+        Self {
+            parent: Vec::new(),
+            still_frames: Vec::new(),
+        }
+    }
+
+    /// Every object starts out on its own island.
+    pub fn reset(&mut self, len: usize) {
+        self.parent.clear();
+        self.parent.extend(0..len);
+    }
+
+    pub fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    pub fn union(&mut self, i: usize, j: usize) {
+        let (root_i, root_j) = (self.find(i), self.find(j));
+        self.parent[root_i] = root_j;
+    }
+
+    /// Patrolling enemies never sleep and don't keep what they stand on awake.
+    pub fn update_sleep(&mut self, objects: &mut [LevelObject]) {
+        // the stillest an island gets is its least still object
+        self.still_frames.clear();
+        self.still_frames.resize(objects.len(), u16::MAX);
+        for (i, obj) in objects.iter_mut().enumerate().filter(|(_, obj)| !obj.is_asleep() && !obj.is_patrolling()) {
+            let root = self.find(i);
+            self.still_frames[root] = self.still_frames[root].min(obj.update_still_frames());
+        }
+
+        for (i, obj) in objects.iter_mut().enumerate().filter(|(_, obj)| !obj.is_asleep() && !obj.is_patrolling()) {
+            let root = self.find(i);
+            if self.still_frames[root] >= SLEEP_FRAMES {
+                obj.sleep();
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let mut islands = Islands::new();
+        islands.reset(5);
+        islands.union(0, 1);
+        islands.union(3, 4);
+        islands.union(1, 4);
+
+        assert_eq!(islands.find(0), islands.find(3));
+        assert_ne!(islands.find(0), islands.find(2));
+    }
+}
@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+use crate::frame::Frame;
+use crate::position::Position;
+use crate::vector::Vector;
+use crate::wasm4::*;
+use crate::palettes;
+
+
+/// Where the player is launched from in a level. Pulling back further than
+/// `max_stretch` does nothing, and launch speed follows
+/// `max_speed * (stretch / max_stretch) ^ power_exponent`.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct Slingshot {
+    /// resting spot of the player's center
+    position: Position,
+    max_stretch: f32,
+    max_speed: f32,
+    power_exponent: f32,
+}
+
+impl Slingshot {
+    const FORK_WIDTH: f32 = 3.0;
+    const FORK_HEIGHT: f32 = 4.0;
+
+    pub const fn new(position: Position, max_stretch: f32, max_speed: f32, power_exponent: f32) -> Self {
+        Self { position, max_stretch, max_speed, power_exponent }
+    }
+
+    pub const fn get_position(&self) -> Position {
+        self.position
+    }
+
+    /// Where the player ends up when pulled towards `target`.
+    pub fn clamp_pull(&self, target: Position) -> Position {
+        let stretch = Vector::between(self.position, target);
+        let length = stretch.magnitude();
+        if length <= self.max_stretch {
+            target
+        } else {
+            self.position + stretch * (self.max_stretch / length)
+        }
+    }
+
+    /// Launch velocity when the player is let go at `release_pos`.
+    pub fn release_velocity(&self, release_pos: Position) -> Vector {
+        let stretch = Vector::between(self.clamp_pull(release_pos), self.position);
+        let length = stretch.magnitude();
+        if length == 0.0 {
+            return Vector::default();
+        }
+        let speed = self.max_speed * (length / self.max_stretch).powf(self.power_exponent);
+        stretch * (speed / length)
+    }
+
+    /// Post, fork and the band running to the player's center.
+    pub fn draw(&self, frame: Frame, player_center: Position) {
+        let left = self.position + Vector::new(-Self::FORK_WIDTH, 0.0);
+        let right = self.position + Vector::new(Self::FORK_WIDTH, 0.0);
+        let base = self.position + Vector::new(0.0, -Self::FORK_HEIGHT);
+        let ground = Position::new(self.position.x, 0.0);
+
+        let [left, right, base, ground, player] = [left, right, base, ground, player_center].map(|p| frame.from_units_to_px(p));
+
+        unsafe { *DRAW_COLORS = palettes::DARK; }
+        line(ground.0, ground.1, base.0, base.1);
+        line(base.0, base.1, left.0, left.1);
+        line(base.0, base.1, right.0, right.1);
+
+        unsafe { *DRAW_COLORS = palettes::LIGHT; }
+        line(left.0, left.1, player.0, player.1);
+        line(right.0, right.1, player.0, player.1);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slingshot() -> Slingshot {
+        Slingshot::new(Position::new(0.0, 10.0), 20.0, 80.0, 2.0)
+    }
+
+    #[test]
+    fn test_pull_is_clamped() {
+        let pulled = slingshot().clamp_pull(Position::new(-40.0, 10.0));
+        assert_eq!(pulled, Position::new(-20.0, 10.0));
+
+        let pulled = slingshot().clamp_pull(Position::new(-5.0, 5.0));
+        assert_eq!(pulled, Position::new(-5.0, 5.0));
+    }
+
+    #[test]
+    fn test_release_velocity_follows_power_curve() {
+        let full = slingshot().release_velocity(Position::new(-20.0, 10.0));
+        assert_eq!(full, Vector::new(80.0, 0.0));
+
+        // pulling further doesn't add power
+        assert_eq!(slingshot().release_velocity(Position::new(-100.0, 10.0)), full);
+
+        // half the stretch with a square curve gives a quarter of the speed
+        let half = slingshot().release_velocity(Position::new(0.0, 0.0));
+        assert_eq!(half, Vector::new(0.0, 20.0));
+    }
+}
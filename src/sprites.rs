@@ -0,0 +1,133 @@
+use crate::animation::{Animation, Playback};
+use crate::wasm4::*;
+
+
+/// 2BPP art, drawn with `Sprite::COLORS`: pixel values 1-3 map to palette colors 2-4
+/// (dark, accent and light, see `palettes`), 0 is transparent.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Sprite {
+    pub width: u32,
+    pub height: u32,
+    data: &'static [u8],
+}
+
+impl Sprite {
+    pub const COLORS: u16 = 0x4320;
+
+    pub const fn new(width: u32, height: u32, data: &'static [u8]) -> Self {
+        Self { width, height, data }
+    }
+
+    pub fn draw(&self, x: i32, y: i32) {
+        unsafe { *DRAW_COLORS = Self::COLORS; }
+        self.draw_part(x, y, 0, 0, self.width, self.height);
+    }
+
+    /// Repeats the sprite for `length` pixels to the right, or downwards if `vertical`,
+    /// cutting the last copy short.
+    pub fn draw_tiled(&self, x: i32, y: i32, length: u32, vertical: bool) {
+        unsafe { *DRAW_COLORS = Self::COLORS; }
+        let tile = if vertical { self.height } else { self.width };
+        for offset in (0..length).step_by(tile as usize) {
+            let cut = tile.min(length - offset);
+            let (x, y, width, height) = if vertical {
+                (x, y + offset as i32, self.width, cut)
+            } else {
+                (x + offset as i32, y, cut, self.height)
+            };
+            self.draw_part(x, y, 0, 0, width, height);
+        }
+    }
+
+    /// Blits the `width`×`height` part of the sprite starting at `src_x`, `src_y`,
+    /// leaving out whatever falls off screen.
+    fn draw_part(&self, x: i32, y: i32, src_x: u32, src_y: u32, width: u32, height: u32) {
+        if let Some(clipped) = clip(x, y, width, height) {
+            blit_sub(
+                self.data, clipped.x, clipped.y, clipped.width, clipped.height,
+                src_x + clipped.src_x, src_y + clipped.src_y, self.width, BLIT_2BPP,
+            );
+        }
+    }
+}
+
+/// The on-screen part of a rectangle drawn at `x`, `y`, and where that part starts within it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Clip {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub src_x: u32,
+    pub src_y: u32,
+}
+
+pub fn clip(x: i32, y: i32, width: u32, height: u32) -> Option<Clip> {
+    let screen = SCREEN_SIZE as i32;
+    let (left, top) = (x.max(0), y.max(0));
+    let (right, bottom) = ((x + width as i32).min(screen), (y + height as i32).min(screen));
+    if left >= right || top >= bottom {
+        return None;
+    }
+    Some(Clip {
+        x: left,
+        y: top,
+        width: (right - left) as u32,
+        height: (bottom - top) as u32,
+        src_x: (left - x) as u32,
+        src_y: (top - y) as u32,
+    })
+}
+
+pub const BOX: Sprite = Sprite::new(8, 8, &[0x55, 0x55, 0x5a, 0xa5, 0x66, 0x99, 0x69, 0x69, 0x69, 0x69, 0x66, 0x99, 0x5a, 0xa5, 0x55, 0x55]);
+pub const NORMAL: Sprite = Sprite::new(8, 8, &[0x00, 0x11, 0x00, 0x15, 0xff, 0xd9, 0xd7, 0xd5, 0xd7, 0xf4, 0xff, 0xf0, 0xcc, 0x30, 0x44, 0x10]);
+pub const LONGHORN: Sprite = Sprite::new(10, 7, &[0xc0, 0x00, 0x73, 0xc0, 0x50, 0xef, 0xb5, 0x4f, 0xbf, 0x64, 0xfe, 0xf5, 0x4c, 0xc3, 0x00, 0x44, 0x10, 0x00]);
+pub const DAIRY: Sprite = Sprite::new(7, 7, &[0x00, 0x17, 0x5f, 0x6d, 0x75, 0x7f, 0xd4, 0xca, 0xc3, 0x30, 0xc4, 0x41, 0x00]);
+pub const CHOCOLATE: Sprite = Sprite::new(9, 9, &[0x00, 0x04, 0x40, 0x01, 0x55, 0x55, 0x65, 0x55, 0x55, 0x65, 0x55, 0x15, 0x55, 0x04, 0x41, 0x01, 0x10, 0x40, 0x44, 0x10, 0x00]);
+pub const FARMER: Sprite = Sprite::new(8, 8, &[0x05, 0x50, 0x15, 0x54, 0x0e, 0xb0, 0x0a, 0xa0, 0x17, 0xd4, 0x05, 0x50, 0x04, 0x10, 0x14, 0x14]);
+pub const SCARECROW: Sprite = Sprite::new(8, 8, &[0x05, 0x50, 0x15, 0x54, 0x02, 0x80, 0xad, 0x7a, 0x03, 0xc0, 0x01, 0x00, 0x01, 0x00, 0x01, 0x00]);
+pub const LOG_HORIZONTAL: Sprite = Sprite::new(8, 3, &[0x66, 0x59, 0xaa, 0xaa, 0x65, 0x99]);
+pub const LOG_VERTICAL: Sprite = Sprite::new(3, 8, &[0x66, 0x96, 0x5a, 0x66, 0x96, 0x5a]);
+
+pub const NORMAL_FLYING: Sprite = Sprite::new(8, 8, &[0x00, 0x11, 0x00, 0x15, 0xff, 0xd9, 0xd7, 0xd5, 0xd7, 0xf4, 0xff, 0xf0, 0x33, 0x30, 0x44, 0x40]);
+pub const LONGHORN_FLYING: Sprite = Sprite::new(10, 7, &[0xc0, 0x00, 0x73, 0xc0, 0x50, 0xef, 0xb5, 0x4f, 0xbf, 0x64, 0xfe, 0xf5, 0x43, 0x33, 0x00, 0x44, 0x40, 0x00]);
+pub const DAIRY_FLYING: Sprite = Sprite::new(7, 7, &[0x00, 0x17, 0x5f, 0x6d, 0x75, 0x7f, 0xd4, 0xca, 0xc0, 0xcc, 0xc4, 0x44, 0x00]);
+pub const CHOCOLATE_FLYING: Sprite = Sprite::new(9, 9, &[0x00, 0x04, 0x40, 0x01, 0x55, 0x55, 0x65, 0x55, 0x55, 0x65, 0x55, 0x15, 0x55, 0x01, 0x11, 0x01, 0x11, 0x00, 0x00, 0x00, 0x00]);
+pub const FARMER_STEP: Sprite = Sprite::new(8, 8, &[0x05, 0x50, 0x15, 0x54, 0x0e, 0xb0, 0x0a, 0xa0, 0x17, 0xd4, 0x05, 0x50, 0x01, 0x40, 0x05, 0x50]);
+pub const BOX_CRACKED: Sprite = Sprite::new(8, 8, &[0x55, 0x15, 0x5a, 0x65, 0x66, 0x19, 0x69, 0x29, 0x61, 0x49, 0x66, 0x19, 0x5a, 0x65, 0x54, 0x55]);
+pub const BOX_SPLIT: Sprite = Sprite::new(8, 8, &[0x54, 0x05, 0x58, 0x09, 0x64, 0x09, 0x68, 0x19, 0x20, 0x08, 0x64, 0x19, 0x58, 0x05, 0x54, 0x15]);
+pub const BOX_DEBRIS: Sprite = Sprite::new(8, 8, &[0x00, 0x00, 0x10, 0x04, 0x02, 0x00, 0x20, 0x04, 0x00, 0x00, 0x42, 0x08, 0x21, 0x04, 0x48, 0x21]);
+
+pub static NORMAL_FLIGHT: Animation = Animation::new(&[(NORMAL, 6), (NORMAL_FLYING, 6)], Playback::Loop);
+pub static LONGHORN_FLIGHT: Animation = Animation::new(&[(LONGHORN, 4), (LONGHORN_FLYING, 4)], Playback::Loop);
+pub static DAIRY_FLIGHT: Animation = Animation::new(&[(DAIRY, 6), (DAIRY_FLYING, 6)], Playback::Loop);
+pub static CHOCOLATE_FLIGHT: Animation = Animation::new(&[(CHOCOLATE, 8), (CHOCOLATE_FLYING, 8)], Playback::Loop);
+pub static FARMER_WALK: Animation = Animation::new(&[(FARMER, 8), (FARMER_STEP, 8)], Playback::Loop);
+pub static BOX_BREAK: Animation = Animation::new(&[(BOX_CRACKED, 4), (BOX_SPLIT, 4), (BOX_DEBRIS, 6)], Playback::OneShot);
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clip() {
+        assert_eq!(clip(10, 20, 8, 8), Some(Clip { x: 10, y: 20, width: 8, height: 8, src_x: 0, src_y: 0 }));
+        assert_eq!(clip(-3, 156, 8, 8), Some(Clip { x: 0, y: 156, width: 5, height: 4, src_x: 3, src_y: 0 }));
+        assert_eq!(clip(40, -6, 8, 8), Some(Clip { x: 40, y: 0, width: 8, height: 2, src_x: 0, src_y: 6 }));
+        assert_eq!(clip(-8, 40, 8, 8), None);
+        assert_eq!(clip(160, 40, 8, 8), None);
+    }
+
+    #[test]
+    fn test_sprite_data_fits_size() {
+        let sprites = [
+            BOX, NORMAL, LONGHORN, DAIRY, CHOCOLATE, FARMER, SCARECROW, LOG_HORIZONTAL, LOG_VERTICAL,
+            NORMAL_FLYING, LONGHORN_FLYING, DAIRY_FLYING, CHOCOLATE_FLYING, FARMER_STEP, BOX_CRACKED, BOX_SPLIT, BOX_DEBRIS,
+        ];
+        for sprite in sprites {
+            let bits = sprite.width * sprite.height * 2;
+            assert_eq!(sprite.data.len() as u32, bits.div_ceil(8));
+        }
+    }
+}
@@ -29,6 +29,26 @@ impl Vector {
     pub fn normalize(&self) -> Self {
         *self / self.magnitude()
     }
+
+    /// z component of the 3d cross product
+    pub fn cross(&self, other: Self) -> f32 {
+        self.x * other.y - self.y * other.x
+    }
+
+    /// Rotates counter-clockwise by `angle` radians.
+    pub fn rotate(&self, angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
+
+    /// Pseudo-random vector with both components in [-1, 1), advancing `seed`.
+    pub fn noise(seed: &mut u32) -> Self {
+        let mut next = || {
+            *seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            (*seed >> 16) as f32 / 32768.0 - 1.0
+        };
+        Self::new(next(), next())
+    }
 }
 
 impl Add for Vector {